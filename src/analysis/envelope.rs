@@ -0,0 +1,193 @@
+use num::{Float, NumCast};
+use option_trait::Maybe;
+
+use crate::{analysis::MovingRms, quantities::{ListOrSingle, Lists, OwnedList}, transforms::fourier::Hilbert};
+
+/// Method of envelope detection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnvelopeType<T>
+{
+    /// Magnitude of the analytic signal.
+    Analytic,
+    /// Root-mean-square over a sliding window of the given width.
+    Rms {
+        width: T
+    },
+    /// Peak detector, which follows rising excursions with the attack time constant, and decays with the release time constant.
+    PeakHold {
+        attack: T,
+        release: T
+    }
+}
+
+impl<T> Default for EnvelopeType<T>
+{
+    fn default() -> Self
+    {
+        Self::Analytic
+    }
+}
+
+pub trait Envelope<T>: Lists<T>
+where
+    T: Float
+{
+    /// Upper and lower envelopes of the signal.
+    ///
+    /// The envelopes are found for the signal with its mean removed, and then offset by the mean. Widths and time constants are
+    /// in seconds given the sampling frequency, or in samples otherwise.
+    fn envelope<FS>(self, envelope_type: EnvelopeType<T>, sampling_frequency: FS) -> Self::RowsMapped<(Self::RowOwned, Self::RowOwned)>
+    where
+        FS: Maybe<T>;
+}
+
+impl<T, L> Envelope<T> for L
+where
+    T: Float,
+    L: Lists<T, RowOwned: OwnedList<T> + Clone>,
+    Vec<T>: Hilbert<T> + MovingRms<T>
+{
+    fn envelope<FS>(self, envelope_type: EnvelopeType<T>, sampling_frequency: FS) -> Self::RowsMapped<(Self::RowOwned, Self::RowOwned)>
+    where
+        FS: Maybe<T>
+    {
+        let fs = sampling_frequency.into_option()
+            .unwrap_or_else(T::one);
+
+        self.map_rows_into_owned(|mut upper| {
+            let mut lower = upper.clone();
+            let n = upper.as_mut_slice().len();
+            if n == 0
+            {
+                return (upper, lower)
+            }
+            let x = upper.as_mut_slice().to_vec();
+            let mean = x.iter()
+                .fold(T::zero(), |a, &b| a + b)/<T as NumCast>::from(n).unwrap();
+            let x: Vec<T> = x.into_iter()
+                .map(|x| x - mean)
+                .collect();
+
+            let (env_upper, env_lower): (Vec<T>, Vec<T>) = match envelope_type
+            {
+                EnvelopeType::Analytic => {
+                    let h = x.clone()
+                        .hilbert();
+                    let env: Vec<T> = x.iter()
+                        .zip(h)
+                        .map(|(&x, h)| x.hypot(h))
+                        .collect();
+                    (env.clone(), env)
+                },
+                EnvelopeType::Rms {width} => {
+                    // Zero-padded by a window's length, so the circular sliding window of movingrms does not wrap around.
+                    let m = <usize as NumCast>::from((width*fs).ceil()).unwrap_or(0).max(1);
+                    let padded: Vec<T> = x.into_iter()
+                        .chain(core::iter::repeat(T::zero()).take(m))
+                        .collect();
+                    let len = <T as NumCast>::from(padded.len() - 1).unwrap();
+                    let time_constant = <T as NumCast>::from(1e-3).unwrap();
+                    let (mut rms, w) = padded.moving_rms(width*fs, time_constant, ());
+
+                    // movingrms normalizes by the length of the signal, rather than the width of the window.
+                    let weight = w.into_iter()
+                        .fold(T::zero(), |a, w| a + w*w);
+                    let scale = (len/weight).sqrt();
+                    rms.truncate(n);
+                    for rms in rms.iter_mut()
+                    {
+                        *rms = *rms*scale
+                    }
+                    (rms.clone(), rms)
+                },
+                EnvelopeType::PeakHold {attack, release} => {
+                    let coeff = |tau: T| T::one() - (-(tau*fs).recip()).exp();
+                    let attack = coeff(attack);
+                    let release = coeff(release);
+                    let follow = |e: T, x: T| {
+                        let coeff = if x > e {attack} else {release};
+                        e + coeff*(x.max(T::zero()) - e)
+                    };
+
+                    let mut eu = T::zero();
+                    let mut el = T::zero();
+                    x.into_iter()
+                        .map(|x| {
+                            eu = follow(eu, x);
+                            el = follow(el, -x);
+                            (eu, el)
+                        }).unzip()
+                }
+            };
+
+            for ((u, l), (eu, el)) in upper.as_mut_slice()
+                .iter_mut()
+                .zip(lower.as_mut_slice()
+                    .iter_mut()
+                ).zip(env_upper.into_iter()
+                    .zip(env_lower)
+                )
+            {
+                *u = mean + eu;
+                *l = mean - el
+            }
+            (upper, lower)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::TAU;
+
+    use crate::analysis::{Envelope, EnvelopeType};
+
+    #[test]
+    fn test()
+    {
+        const FS: f64 = 8000.0;
+        const N: usize = 4000;
+
+        // Amplitude modulated carrier, with an offset.
+        let a = |t: f64| 1.0 + 0.5*(TAU*4.0*t).cos();
+        let x: Vec<f64> = (0..N).map(|i| i as f64/FS)
+            .map(|t| 0.25 + a(t)*(TAU*1000.0*t).sin())
+            .collect();
+
+        let (upper, lower) = x.clone()
+            .envelope(EnvelopeType::Analytic, FS);
+        for (i, (u, l)) in upper.into_iter()
+            .zip(lower)
+            .enumerate()
+            .skip(N/10)
+            .take(N*8/10)
+        {
+            let a = a(i as f64/FS);
+            assert!((u - 0.25 - a).abs() < 0.05);
+            assert!((0.25 - l - a).abs() < 0.05);
+        }
+
+        // The RMS of a sine is its amplitude over the square root of two.
+        let (upper, _) = x.clone()
+            .envelope(EnvelopeType::Rms {width: 0.005}, FS);
+        for (i, u) in upper.into_iter()
+            .enumerate()
+            .skip(N/10)
+            .take(N*8/10)
+        {
+            assert!((u - 0.25 - a(i as f64/FS)/2f64.sqrt()).abs() < 0.1);
+        }
+
+        let (upper, lower) = x.envelope(EnvelopeType::PeakHold {attack: 0.0, release: 0.01}, FS);
+        for (i, (u, l)) in upper.into_iter()
+            .zip(lower)
+            .enumerate()
+            .skip(N/10)
+        {
+            let a = a(i as f64/FS);
+            assert!(u - 0.25 <= a + 1e-9 && u - 0.25 > 0.8*a);
+            assert!(0.25 - l <= a + 1e-9 && 0.25 - l > 0.8*a);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use num::{traits::FloatConst, Float, NumCast};
+use option_trait::Maybe;
+
+use crate::{operations::Unwrap, quantities::{ListOrSingle, Lists, OwnedList}, transforms::fourier::Hilbert};
+
+pub trait InstFreq<T>: Lists<T>
+where
+    T: Float
+{
+    /// Instantaneous amplitude, phase and frequency of the signal, from its analytic signal.
+    ///
+    /// The phase is unwrapped, and the frequency is its central difference, in hertz given the sampling frequency, or in cycles
+    /// per sample otherwise. The frequency is smoothed with a centered moving average over `smoothing` samples (default 1, i.e.
+    /// no smoothing).
+    ///
+    /// Returns the amplitude, phase and frequency.
+    fn inst_freq<FS, S>(self, sampling_frequency: FS, smoothing: S) -> Self::RowsMapped<(Self::RowOwned, Self::RowOwned, Self::RowOwned)>
+    where
+        FS: Maybe<T>,
+        S: Maybe<usize>;
+}
+
+impl<T, L> InstFreq<T> for L
+where
+    T: Float + FloatConst,
+    L: Lists<T, RowOwned: OwnedList<T> + Clone>,
+    Vec<T>: Hilbert<T> + Unwrap<T>
+{
+    fn inst_freq<FS, S>(self, sampling_frequency: FS, smoothing: S) -> Self::RowsMapped<(Self::RowOwned, Self::RowOwned, Self::RowOwned)>
+    where
+        FS: Maybe<T>,
+        S: Maybe<usize>
+    {
+        let fs = sampling_frequency.into_option()
+            .unwrap_or_else(T::one);
+        let smoothing = smoothing.into_option()
+            .unwrap_or(1)
+            .max(1);
+        let two = T::one() + T::one();
+
+        self.map_rows_into_owned(|mut amplitude| {
+            let mut phase = amplitude.clone();
+            let mut frequency = amplitude.clone();
+            let x = amplitude.as_mut_slice().to_vec();
+            let n = x.len();
+
+            let h = x.clone()
+                .hilbert();
+            let theta: Vec<T> = x.iter()
+                .zip(h.iter())
+                .map(|(&x, &h)| h.atan2(x))
+                .collect::<Vec<T>>()
+                .unwrap(());
+
+            let scale = fs/T::TAU();
+            let omega: Vec<T> = (0..n).map(|i| {
+                    let d = match (i.checked_sub(1), (i + 1 < n).then_some(i + 1))
+                    {
+                        (Some(a), Some(b)) => (theta[b] - theta[a])/two,
+                        (None, Some(b)) => theta[b] - theta[i],
+                        (Some(a), None) => theta[i] - theta[a],
+                        (None, None) => T::zero()
+                    };
+                    d*scale
+                }).collect();
+
+            // Centered moving average, truncated at the edges.
+            let k = smoothing/2;
+            let f = (0..n).map(|i| {
+                let window = &omega[i.saturating_sub(k)..(i + smoothing - k).min(n)];
+                window.iter()
+                    .fold(T::zero(), |a, &b| a + b)/<T as NumCast>::from(window.len()).unwrap()
+            });
+
+            for ((((a, p), w), (&x, &h)), (&t, f)) in amplitude.as_mut_slice()
+                .iter_mut()
+                .zip(phase.as_mut_slice()
+                    .iter_mut()
+                ).zip(frequency.as_mut_slice()
+                    .iter_mut()
+                ).zip(x.iter()
+                    .zip(h.iter())
+                ).zip(theta.iter()
+                    .zip(f)
+                )
+            {
+                *a = x.hypot(h);
+                *p = t;
+                *w = f
+            }
+            (amplitude, phase, frequency)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::TAU;
+
+    use crate::analysis::InstFreq;
+
+    #[test]
+    fn test()
+    {
+        const FS: f64 = 1000.0;
+        const N: usize = 1000;
+
+        // Frequency modulated tone, swinging ±20 Hz around 100 Hz twice per second.
+        let f = |t: f64| 100.0 + 20.0*(TAU*2.0*t).sin();
+        let x: Vec<f64> = (0..N).map(|i| i as f64/FS)
+            .map(|t| (TAU*100.0*t - 20.0/2.0*((TAU*2.0*t).cos() - 1.0)).cos())
+            .collect();
+
+        let (a, _, w) = x.inst_freq(FS, 5);
+        for (i, (a, w)) in a.into_iter()
+            .zip(w)
+            .enumerate()
+            .skip(N/10)
+            .take(N*8/10)
+        {
+            assert!((a - 1.0).abs() < 0.05);
+            assert!((w - f(i as f64/FS)).abs() < 1.0);
+        }
+    }
+}
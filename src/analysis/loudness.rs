@@ -0,0 +1,245 @@
+use core::ops::{AddAssign, SubAssign};
+
+use num::{rational::Ratio, Float, NumCast};
+use thiserror::Error;
+
+use crate::{gen::filter::KWeighting, operations::{filtering::FilterMut, resampling::Resample}, quantities::{Lists, MaybeList}, systems::{Rtf, Sos, Tf}};
+
+/// Offset of the loudness formula of ITU-R BS.1770, in dB.
+const LOUDNESS_OFFSET: f64 = -0.691;
+const ABSOLUTE_GATE: f64 = -70.0;
+const RELATIVE_GATE_INTEGRATED: f64 = -10.0;
+const RELATIVE_GATE_RANGE: f64 = -20.0;
+const MOMENTARY_WINDOW: f64 = 0.4;
+const SHORT_TERM_WINDOW: f64 = 3.0;
+const BLOCK_STEP: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum LoudnessError
+{
+    #[error("Sampling frequency must be a positive number, and above twice the K-weighting shelf frequency.")]
+    InvalidSamplingFrequency,
+    #[error("All channels must have equal length.")]
+    ChannelsDifferentLength,
+    #[error("List of channel weights must have one weight per channel.")]
+    ChannelsAndWeightsDifferentLength,
+    #[error("Signal must be at least as long as a momentary loudness block (400 ms).")]
+    SignalTooShort
+}
+
+/// Loudness measures of a programme, as required by EBU R128.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoudnessMeasurement<T>
+where
+    T: Float
+{
+    /// Gated integrated loudness in LUFS. Negative infinity if every block was gated out.
+    pub integrated: T,
+    /// Momentary loudness in LUFS, for 400 ms blocks every 100 ms.
+    pub momentary: Vec<T>,
+    /// Short-term loudness in LUFS, for 3 s blocks every 100 ms.
+    pub short_term: Vec<T>,
+    /// Loudness range (EBU Tech 3342) in LU.
+    pub loudness_range: T,
+    /// Maximum true peak across all channels in dBTP, estimated by oversampling.
+    pub true_peak: T
+}
+
+/// Loudness measurement of ITU-R BS.1770-4 / EBU R128.
+///
+/// Each row is a channel. If no channel weights are given, every channel is weighted equally with 1, which is correct for
+/// mono, stereo and the front channels of surround formats. Surround channels should be weighted with 1.41, and LFE channels
+/// should be left out.
+pub trait Loudness<T, W>: Lists<T>
+where
+    T: Float,
+    W: MaybeList<T>
+{
+    fn loudness(&self, channel_weights: W, sampling_frequency: T) -> Result<LoudnessMeasurement<T>, LoudnessError>;
+}
+
+impl<T, L, W> Loudness<T, W> for L
+where
+    T: Float + AddAssign + SubAssign,
+    L: Lists<T>,
+    W: MaybeList<T>,
+    Sos<T, [T; 3], [T; 3], [Tf<T, [T; 3], [T; 3]>; 2]>: KWeighting,
+    Rtf<T, Sos<T, [T; 3], [T; 3], [Tf<T, [T; 3], [T; 3]>; 2]>>: FilterMut<T, Vec<T>, Output = Vec<T>>,
+    Vec<T>: Resample<T, (), Ratio<usize>, Vec<T>> + Lists<T, RowsMapped<Vec<T>> = Vec<T>>
+{
+    fn loudness(&self, channel_weights: W, sampling_frequency: T) -> Result<LoudnessMeasurement<T>, LoudnessError>
+    {
+        let zero = T::zero();
+        let one = T::one();
+        let ten = <T as NumCast>::from(10.0).unwrap();
+        let twenty = ten + ten;
+        let c = |x: f64| <T as NumCast>::from(x).unwrap();
+
+        let x = self.as_view_slices();
+        let n = x.first()
+            .map(|x| x.len())
+            .unwrap_or(0);
+        if x.iter().any(|x| x.len() != n)
+        {
+            return Err(LoudnessError::ChannelsDifferentLength)
+        }
+        let g = match channel_weights.as_view_slice_option()
+        {
+            Some(g) => {
+                if g.len() != x.len()
+                {
+                    return Err(LoudnessError::ChannelsAndWeightsDifferentLength)
+                }
+                g.to_vec()
+            },
+            None => vec![one; x.len()]
+        };
+
+        let k_weighting: Sos<T, [T; 3], [T; 3], [Tf<T, [T; 3], [T; 3]>; 2]> = Sos::k_weighting(sampling_frequency)
+            .map_err(|_| LoudnessError::InvalidSamplingFrequency)?;
+
+        let samples = |t: f64| <usize as NumCast>::from((c(t)*sampling_frequency).round())
+            .unwrap()
+            .max(1);
+        let momentary_length = samples(MOMENTARY_WINDOW);
+        let short_term_length = samples(SHORT_TERM_WINDOW);
+        let step = samples(BLOCK_STEP);
+        if n < momentary_length
+        {
+            return Err(LoudnessError::SignalTooShort)
+        }
+
+        // Channel-weighted sum of mean squares, accumulated so that any block can be averaged directly.
+        let mut cumsum = vec![zero; n + 1];
+        for (x, &g) in x.iter()
+            .zip(g.iter())
+        {
+            let y = Rtf::new(k_weighting.clone(), ())
+                .filter_mut(x.to_vec());
+            let mut sum = zero;
+            for (s, y) in cumsum.iter_mut()
+                .skip(1)
+                .zip(y.into_iter())
+            {
+                sum += g*y*y;
+                *s += sum
+            }
+        }
+        let block_powers = |length: usize| -> Vec<T> {
+            let l = <T as NumCast>::from(length).unwrap();
+            (0..)
+                .map(|k| k*step)
+                .take_while(|&i| i + length <= n)
+                .map(|i| (cumsum[i + length] - cumsum[i])/l)
+                .collect()
+        };
+        let loudness = |z: T| c(LOUDNESS_OFFSET) + ten*z.log10();
+
+        // Blocks above the absolute gate, and above the given gate relative to their own mean.
+        let gate = |z: &[T], relative: f64| -> Vec<T> {
+            let absolute: Vec<T> = z.iter()
+                .copied()
+                .filter(|&z| loudness(z) > c(ABSOLUTE_GATE))
+                .collect();
+            if absolute.is_empty()
+            {
+                return absolute
+            }
+            let mean = absolute.iter()
+                .copied()
+                .fold(zero, |a, b| a + b)/<T as NumCast>::from(absolute.len()).unwrap();
+            let threshold = loudness(mean) + c(relative);
+            absolute.into_iter()
+                .filter(|&z| loudness(z) > threshold)
+                .collect()
+        };
+
+        let momentary_power = block_powers(momentary_length);
+        let short_term_power = block_powers(short_term_length);
+
+        let gated = gate(&momentary_power, RELATIVE_GATE_INTEGRATED);
+        let integrated = if gated.is_empty()
+        {
+            T::neg_infinity()
+        }
+        else
+        {
+            loudness(gated.iter()
+                .copied()
+                .fold(zero, |a, b| a + b)/<T as NumCast>::from(gated.len()).unwrap()
+            )
+        };
+
+        let mut range: Vec<T> = gate(&short_term_power, RELATIVE_GATE_RANGE).into_iter()
+            .map(loudness)
+            .collect();
+        range.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let percentile = |p: f64| range[<usize as NumCast>::from((c(p)*<T as NumCast>::from(range.len() - 1).unwrap()).round()).unwrap()];
+        let loudness_range = if range.is_empty()
+        {
+            zero
+        }
+        else
+        {
+            percentile(0.95) - percentile(0.10)
+        };
+
+        // Oversampled by 4 below 96 kHz and by 2 below 192 kHz, as recommended in annex 2 of BS.1770-4.
+        let factor = if sampling_frequency < c(96000.0)
+        {
+            4
+        }
+        else if sampling_frequency < c(192000.0)
+        {
+            2
+        }
+        else
+        {
+            1
+        };
+        let true_peak = x.iter()
+            .map(|x| {
+                let x = x.to_vec();
+                if factor > 1 {x.resample((), Ratio::new(factor, 1), (), ())} else {x}
+            }).flat_map(|y| y.into_iter())
+            .map(Float::abs)
+            .fold(zero, Float::max);
+
+        Ok(LoudnessMeasurement {
+            integrated,
+            momentary: momentary_power.into_iter()
+                .map(loudness)
+                .collect(),
+            short_term: short_term_power.into_iter()
+                .map(loudness)
+                .collect(),
+            loudness_range,
+            true_peak: twenty*true_peak.log10()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::TAU;
+
+    use crate::analysis::Loudness;
+
+    #[test]
+    fn test()
+    {
+        const FS: f64 = 48000.0;
+
+        // EBU Tech 3341: a stereo 1 kHz sine at -23 dBFS in both channels reads -23 LUFS.
+        let a = 10f64.powf(-23.0/20.0);
+        let x: Vec<f64> = (0..20*48000).map(|i| a*(TAU*1000.0*i as f64/FS).sin())
+            .collect();
+        let l = [x.clone(), x].loudness((), FS)
+            .unwrap();
+
+        assert!((l.integrated + 23.0).abs() < 0.1);
+        assert!(l.loudness_range < 0.1);
+        assert!((l.true_peak + 23.0).abs() < 0.1);
+    }
+}
@@ -2,6 +2,7 @@ moddef::moddef!(
     flat(pub) mod {
         cceps,
         cpsd,
+        envelope,
         filternorm,
         filtic_u,
         filtic,
@@ -15,20 +16,25 @@ moddef::moddef!(
         impulse_s,
         impulse_z,
         impz,
+        inst_freq,
         isallpass,
         islinphase,
         ismaxphase,
         isminphase,
         isstable,
+        loudness,
         movingrms,
         mscohere,
+        octave_band_levels,
         pburg,
         peak_to_peak,
         peak_to_rms,
         phasez,
+        pole_sensitivity,
         psd,
         pwelch,
         pyulear,
+        quantization_analysis,
         rceps,
         real_cpsd,
         real_freqz,
@@ -40,6 +46,7 @@ moddef::moddef!(
         rssq,
         sim_s,
         sim_z,
+        sound_level_meter,
         specgram,
         statelevels,
         step_s,
@@ -0,0 +1,126 @@
+use core::ops::{AddAssign, SubAssign};
+
+use num::{Float, NumCast};
+
+use crate::{gen::filter::{OctaveBand, OctaveFilterBank}, operations::filtering::FilterMut, systems::{Rtf, Sos, Tf}};
+
+/// Running fractional-octave band level analysis.
+///
+/// Each band's mean-square output is integrated over consecutive blocks of `integration_time`, and reported as a level in dB
+/// relative to `reference`. The filter states and any incomplete block are kept between calls, so a signal may be processed
+/// in arbitrary chunks.
+pub struct OctaveBandLevels<T>
+where
+    T: Float
+{
+    pub bands: Vec<OctaveBand<T>>,
+    filters: Vec<Rtf<T, Sos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>>>>,
+    block_length: usize,
+    reference: T,
+    sum: Vec<T>,
+    count: usize
+}
+
+impl<T> OctaveBandLevels<T>
+where
+    T: Float + AddAssign + SubAssign,
+    Rtf<T, Sos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>>>: FilterMut<T, Vec<T>, Output = Vec<T>>
+{
+    pub fn new(bank: OctaveFilterBank<T>, integration_time: T, reference: T) -> Self
+    {
+        let block_length = <usize as NumCast>::from((integration_time*bank.sampling_frequency).round())
+            .unwrap_or(1)
+            .max(1);
+        let n = bank.filters.len();
+
+        Self {
+            bands: bank.bands,
+            filters: bank.filters.into_iter()
+                .map(|sos| Rtf::new(sos, ()))
+                .collect(),
+            block_length,
+            reference,
+            sum: vec![T::zero(); n],
+            count: 0
+        }
+    }
+
+    /// Filters the next chunk of the signal, returning the levels of every block completed within it, for each band.
+    pub fn process(&mut self, x: &[T]) -> Vec<Vec<T>>
+    {
+        let ten = <T as NumCast>::from(10.0).unwrap();
+        let reference2 = self.reference*self.reference;
+        let n = <T as NumCast>::from(self.block_length).unwrap();
+
+        let y: Vec<Vec<T>> = self.filters.iter_mut()
+            .map(|rtf| rtf.filter_mut(x.to_vec()))
+            .collect();
+
+        let mut levels = vec![vec![]; self.filters.len()];
+        for k in 0..x.len()
+        {
+            for (sum, y) in self.sum.iter_mut()
+                .zip(y.iter())
+            {
+                *sum += y[k]*y[k]
+            }
+            self.count += 1;
+
+            if self.count == self.block_length
+            {
+                for (level, sum) in levels.iter_mut()
+                    .zip(self.sum.iter_mut())
+                {
+                    level.push(ten*(*sum/n/reference2).log10());
+                    *sum = T::zero()
+                }
+                self.count = 0
+            }
+        }
+
+        levels
+    }
+
+    /// Resets the filter states and discards any incomplete block.
+    pub fn reset(&mut self)
+    {
+        for rtf in self.filters.iter_mut()
+        {
+            rtf.w.clear()
+        }
+        self.sum.fill(T::zero());
+        self.count = 0
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::TAU;
+
+    use crate::{analysis::OctaveBandLevels, gen::filter::{OctaveFilterBank, OctaveFraction}};
+
+    #[test]
+    fn test()
+    {
+        const FS: f64 = 48000.0;
+
+        let bank = OctaveFilterBank::new(OctaveFraction::Full, [31.5, 16000.0], 3, FS)
+            .unwrap();
+        let mut meter = OctaveBandLevels::new(bank, 0.125, 1.0);
+
+        let x: Vec<f64> = (0..48000).map(|i| (TAU*1000.0*i as f64/FS).sin())
+            .collect();
+        let levels = meter.process(&x);
+
+        let k = meter.bands.iter()
+            .position(|band| band.index == 0)
+            .unwrap();
+        let l = *levels[k].last().unwrap();
+        assert!((l - 10.0*0.5f64.log10()).abs() < 0.2);
+        assert!(levels.iter()
+            .enumerate()
+            .all(|(i, l)| i == k || *l.last().unwrap() < -10.0)
+        );
+    }
+}
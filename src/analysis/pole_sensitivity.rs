@@ -0,0 +1,148 @@
+use ndarray::Array2;
+use num::{complex::ComplexFloat, Complex, Float, One, Zero};
+
+use crate::{System, systems::{Sos, Tf, Zpk}, transforms::system::ToZpk};
+
+/// Sensitivity of each pole to each denominator coefficient, ∂p_i/∂a_k, for the coefficients a_1 to a_N of the realization.
+///
+/// For direct form, every pole depends on every coefficient. For second-order sections, each pole depends only on the two
+/// coefficients of its own section, in the order the sections are stored. Returns the poles along with the sensitivities, one
+/// row per pole.
+pub trait PoleSensitivity: System
+where
+    Self::Set: Float
+{
+    fn pole_sensitivity(&self) -> (Vec<Complex<Self::Set>>, Array2<Complex<Self::Set>>);
+}
+
+/// ∂p_i/∂a_k for a polynomial a_0 z^N + ... + a_N with roots p.
+fn root_sensitivity<T>(a0: T, p: &[Complex<T>]) -> Array2<Complex<T>>
+where
+    T: Float
+{
+    let n = p.len();
+    Array2::from_shape_fn((n, n), |(i, k)| {
+        let d = p.iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, &pj)| p[i] - pj)
+            .fold(Complex::one(), |d, x| d*x)*a0;
+        -p[i].powi((n - 1 - k) as i32)/d
+    })
+}
+
+impl<T> PoleSensitivity for Tf<T, Vec<T>, Vec<T>>
+where
+    T: Float,
+    Tf<T, Vec<T>, Vec<T>>: ToZpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T, (), ()> + System<Set = T>
+{
+    fn pole_sensitivity(&self) -> (Vec<Complex<T>>, Array2<Complex<T>>)
+    {
+        let a = self.a.to_vec();
+        let a0 = a.first()
+            .copied()
+            .unwrap_or_else(T::one);
+        let p: Vec<Complex<T>> = Tf::new(vec![T::one()], a)
+            .to_zpk((), ())
+            .p
+            .into_inner();
+
+        let s = root_sensitivity(a0, &p);
+        (p, s)
+    }
+}
+
+impl<T> PoleSensitivity for Sos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>>
+where
+    T: Float,
+    Self: System<Set = T>
+{
+    fn pole_sensitivity(&self) -> (Vec<Complex<T>>, Array2<Complex<T>>)
+    {
+        let two = T::one() + T::one();
+        let four = two*two;
+
+        let mut poles = vec![];
+        let mut blocks = vec![];
+        for tf in self.sos.iter()
+        {
+            let [a0, a1, a2] = *tf.a;
+            let p: Vec<Complex<T>> = if a2.is_zero()
+            {
+                if a1.is_zero() {vec![]} else {vec![Complex::from(-a1/a0)]}
+            }
+            else
+            {
+                let d = Complex::from(a1*a1 - four*a0*a2).sqrt();
+                vec![(-d - a1)/(two*a0), (d - a1)/(two*a0)]
+            };
+            let s = if p.len() == 2
+            {
+                root_sensitivity(a0, &p)
+            }
+            else
+            {
+                // A first-order section only depends on a_1.
+                Array2::from_shape_fn((p.len(), 2), |(_, k)| if k == 0 {-Complex::from(a0.recip())} else {Complex::zero()})
+            };
+            poles.extend(p);
+            blocks.push(s);
+        }
+
+        let m = blocks.len();
+        let mut s = Array2::from_elem((poles.len(), 2*m), Complex::zero());
+        let mut i = 0;
+        for (k, block) in blocks.into_iter()
+            .enumerate()
+        {
+            for ((r, c), &x) in block.indexed_iter()
+            {
+                s[(i + r, 2*k + c)] = x
+            }
+            i += block.nrows()
+        }
+
+        (poles, s)
+    }
+}
+
+impl<T> PoleSensitivity for Zpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T>
+where
+    T: Float,
+    Self: System<Set = T>
+{
+    /// Poles are their own coefficients in this form, so the sensitivity is the identity.
+    fn pole_sensitivity(&self) -> (Vec<Complex<T>>, Array2<Complex<T>>)
+    {
+        let p = self.p.to_vec();
+        let n = p.len();
+        (p, Array2::from_shape_fn((n, n), |(i, k)| if i == k {Complex::one()} else {Complex::zero()}))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use num::Complex;
+
+    use crate::{analysis::PoleSensitivity, systems::Tf};
+
+    #[test]
+    fn test()
+    {
+        // Poles 0.5 and 0.9: dp/da_k = -p^(N - k)/(p - p').
+        let h = Tf::new(vec![1.0], vec![1.0, -1.4, 0.45]);
+        let (p, s) = h.pole_sensitivity();
+
+        for (i, &p_i) in p.iter()
+            .enumerate()
+        {
+            let p_j = p[1 - i];
+            for (k, e) in [p_i, Complex::new(1.0, 0.0)].into_iter()
+                .enumerate()
+            {
+                assert!((s[(i, k)] + e/(p_i - p_j)).norm() < 1e-9)
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use num::{Complex, Float, NumCast};
+
+use crate::{analysis::{FreqZ, IsStable}, operations::{EncodeOverflow, FixedPointFormat, QuantizeRounding}, Plane, System, systems::{Tf, Zpk}, transforms::{filter::QuantizeCoefficients, system::{ToTf, ToZpk}}};
+
+/// Effects of coefficient quantization on a filter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantizationReport<T>
+where
+    T: Float
+{
+    /// Distance from each zero of the original filter to the nearest zero of the quantized filter.
+    pub zero_displacement: Vec<T>,
+    /// Distance from each pole of the original filter to the nearest pole of the quantized filter.
+    pub pole_displacement: Vec<T>,
+    pub is_stable: bool,
+    /// Magnitude response of the quantized filter relative to the original filter in dB, at the frequencies `frequencies`.
+    pub response_deviation: Vec<T>,
+    pub frequencies: Vec<T>,
+    /// Largest absolute value of the response deviation.
+    pub max_response_deviation: T
+}
+
+/// Quantizes the coefficients of a filter and compares the result to the original.
+///
+/// The frequency response is compared at `n` frequencies, and the returned frequencies are normalized so that 1 is the
+/// Nyquist frequency.
+pub trait QuantizationAnalysis: System + Sized
+where
+    Self::Set: Float
+{
+    fn quantization_analysis(
+        &self,
+        format: FixedPointFormat,
+        rounding: QuantizeRounding,
+        overflow: EncodeOverflow,
+        n: usize
+    ) -> (Self, QuantizationReport<Self::Set>);
+}
+
+impl<T, S> QuantizationAnalysis for S
+where
+    T: Float,
+    S: System<Set = T> + QuantizeCoefficients + Clone
+        + ToZpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T, (), ()>
+        + ToTf<T, Vec<T>, Vec<T>, (), ()>,
+    Zpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T>: for<'a> IsStable<'a, Output = bool> + System<Set = T>,
+    Tf<T, Vec<T>, Vec<T>>: for<'a> FreqZ<'a, Vec<Complex<T>>, Vec<T>, usize> + System<Set = T>
+{
+    fn quantization_analysis(
+        &self,
+        format: FixedPointFormat,
+        rounding: QuantizeRounding,
+        overflow: EncodeOverflow,
+        n: usize
+    ) -> (Self, QuantizationReport<T>)
+    {
+        let twenty = <T as NumCast>::from(20.0).unwrap();
+
+        let quantized = self.clone()
+            .quantize_coefficients(format, rounding, overflow);
+
+        let zpk = self.clone()
+            .to_zpk((), ());
+        let zpk_q = quantized.clone()
+            .to_zpk((), ());
+
+        // Pairs each root with the nearest root of the quantized filter not yet taken.
+        let displacement = |r: &[Complex<T>], rq: &[Complex<T>]| {
+            let mut taken = vec![false; rq.len()];
+            r.iter()
+                .map(|&r| {
+                    let nearest = rq.iter()
+                        .enumerate()
+                        .filter(|&(j, _)| !taken[j])
+                        .map(|(j, &rq)| (j, (rq - r).norm()))
+                        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(core::cmp::Ordering::Equal));
+                    match nearest
+                    {
+                        Some((j, d)) => {
+                            taken[j] = true;
+                            d
+                        },
+                        None => T::infinity()
+                    }
+                }).collect::<Vec<_>>()
+        };
+        let zero_displacement = displacement(&zpk.z, &zpk_q.z);
+        let pole_displacement = displacement(&zpk.p, &zpk_q.p);
+        let is_stable = zpk_q.is_stable((), Plane::Z);
+
+        let tf: Tf<T, Vec<T>, Vec<T>> = self.clone()
+            .to_tf((), ());
+        let tf_q: Tf<T, Vec<T>, Vec<T>> = quantized.clone()
+            .to_tf((), ());
+        let (h, w): (Vec<Complex<T>>, Vec<T>) = tf.freqz(n, false);
+        let (h_q, _): (Vec<Complex<T>>, Vec<T>) = tf_q.freqz(n, false);
+
+        let response_deviation: Vec<T> = h.iter()
+            .zip(h_q.iter())
+            .map(|(h, h_q)| twenty*(h_q.norm()/h.norm()).log10())
+            .collect();
+        let max_response_deviation = response_deviation.iter()
+            .copied()
+            .filter(|d| !d.is_nan())
+            .map(Float::abs)
+            .fold(T::zero(), Float::max);
+        let frequencies = w.into_iter()
+            .map(|w| w/<T as NumCast>::from(core::f64::consts::PI).unwrap())
+            .collect();
+
+        (quantized, QuantizationReport {
+            zero_displacement,
+            pole_displacement,
+            is_stable,
+            response_deviation,
+            frequencies,
+            max_response_deviation
+        })
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use crate::{analysis::QuantizationAnalysis, gen::filter::{Butter, FilterGenPlane, FilterGenType}, operations::{EncodeOverflow, FixedPointFormat, QuantizeRounding}, systems::{Sos, Tf}};
+
+    #[test]
+    fn test()
+    {
+        let h: Sos<f64, [_; 3], [_; 3], Vec<_>> = Sos::butter(6, [0.05], FilterGenType::LowPass, FilterGenPlane::Z { sampling_frequency: None })
+            .unwrap();
+        let (_, report) = h.quantization_analysis(FixedPointFormat::q(1, 14), QuantizeRounding::Nearest, EncodeOverflow::Saturate, 512);
+        assert!(report.is_stable);
+
+        let h: Tf<f64, Vec<_>, Vec<_>> = Tf::butter(6, [0.05], FilterGenType::LowPass, FilterGenPlane::Z { sampling_frequency: None })
+            .unwrap();
+        let (_, report_df) = h.quantization_analysis(FixedPointFormat::q(1, 14), QuantizeRounding::Nearest, EncodeOverflow::Saturate, 512);
+
+        // Second-order sections are far less sensitive than direct form.
+        let max = |d: &[f64]| d.iter().copied().fold(0.0, f64::max);
+        assert!(max(&report.pole_displacement) < max(&report_df.pole_displacement));
+    }
+}
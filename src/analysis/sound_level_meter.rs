@@ -0,0 +1,153 @@
+use core::ops::{AddAssign, SubAssign};
+
+use num::{Float, NumCast};
+
+use crate::{gen::filter::{FilterGenError, FilterGenPlane, FrequencyWeighting, WeightingFilter}, operations::filtering::FilterMut, systems::{Rtf, Sos, Tf}};
+
+/// Time weightings of IEC 61672-1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeWeighting
+{
+    /// 125 ms exponential averaging.
+    Fast,
+    /// 1 s exponential averaging.
+    Slow,
+    /// 35 ms exponential averaging on rising levels, with a 1.5 s decay.
+    Impulse
+}
+
+impl TimeWeighting
+{
+    /// Time constants for rising and falling levels, in seconds.
+    pub fn time_constants<T>(self) -> (T, T)
+    where
+        T: Float
+    {
+        let (rise, fall) = match self
+        {
+            TimeWeighting::Fast => (0.125, 0.125),
+            TimeWeighting::Slow => (1.0, 1.0),
+            TimeWeighting::Impulse => (0.035, 1.5)
+        };
+        (T::from(rise).unwrap(), T::from(fall).unwrap())
+    }
+}
+
+/// Sound level meter processor.
+///
+/// The signal is frequency weighted, squared and exponentially averaged according to the time weighting, and reported as a level
+/// in dB relative to `reference` (e.g. 20 µPa). The weighting filter state and averager state are kept between calls.
+pub struct SoundLevelMeter<T>
+where
+    T: Float
+{
+    filter: Rtf<T, Sos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>>>,
+    alpha_rise: T,
+    alpha_fall: T,
+    reference: T,
+    mean_square: T,
+    energy: T,
+    count: usize,
+    max: T
+}
+
+impl<T> SoundLevelMeter<T>
+where
+    T: Float + AddAssign + SubAssign,
+    Sos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>>: WeightingFilter,
+    Rtf<T, Sos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>>>: FilterMut<T, Vec<T>, Output = Vec<T>>
+{
+    pub fn new(
+        frequency_weighting: FrequencyWeighting,
+        time_weighting: TimeWeighting,
+        reference: T,
+        sampling_frequency: T
+    ) -> Result<Self, FilterGenError>
+    {
+        let sos = Sos::weighting_filter(frequency_weighting, FilterGenPlane::Z { sampling_frequency: Some(sampling_frequency) })?;
+        let (rise, fall): (T, T) = time_weighting.time_constants();
+
+        Ok(Self {
+            filter: Rtf::new(sos, ()),
+            alpha_rise: T::one() - (-(rise*sampling_frequency).recip()).exp(),
+            alpha_fall: T::one() - (-(fall*sampling_frequency).recip()).exp(),
+            reference,
+            mean_square: T::zero(),
+            energy: T::zero(),
+            count: 0,
+            max: T::zero()
+        })
+    }
+
+    /// Processes the next chunk of the signal, returning the time-weighted sound level for each sample.
+    pub fn process(&mut self, x: &[T]) -> Vec<T>
+    {
+        let ten = <T as NumCast>::from(10.0).unwrap();
+        let reference2 = self.reference*self.reference;
+
+        let y = self.filter.filter_mut(x.to_vec());
+
+        y.into_iter()
+            .map(|y| {
+                let y2 = y*y;
+                let alpha = if y2 > self.mean_square {self.alpha_rise} else {self.alpha_fall};
+                self.mean_square = self.mean_square + alpha*(y2 - self.mean_square);
+                self.energy += y2;
+                self.count += 1;
+                if self.mean_square > self.max
+                {
+                    self.max = self.mean_square
+                }
+                ten*(self.mean_square/reference2).log10()
+            }).collect()
+    }
+
+    /// Equivalent continuous sound level of everything processed since creation or the last reset.
+    pub fn equivalent_level(&self) -> T
+    {
+        let ten = <T as NumCast>::from(10.0).unwrap();
+        let n = <T as NumCast>::from(self.count.max(1)).unwrap();
+        ten*(self.energy/n/(self.reference*self.reference)).log10()
+    }
+
+    /// Maximum time-weighted sound level since creation or the last reset.
+    pub fn max_level(&self) -> T
+    {
+        let ten = <T as NumCast>::from(10.0).unwrap();
+        ten*(self.max/(self.reference*self.reference)).log10()
+    }
+
+    pub fn reset(&mut self)
+    {
+        self.filter.w.clear();
+        self.mean_square = T::zero();
+        self.energy = T::zero();
+        self.count = 0;
+        self.max = T::zero()
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::TAU;
+
+    use crate::{analysis::{SoundLevelMeter, TimeWeighting}, gen::filter::FrequencyWeighting};
+
+    #[test]
+    fn test()
+    {
+        const FS: f64 = 48000.0;
+
+        let mut slm = SoundLevelMeter::new(FrequencyWeighting::A, TimeWeighting::Fast, 20e-6, FS)
+            .unwrap();
+
+        // 1 Pa RMS at 1 kHz is 94 dB.
+        let x: Vec<f64> = (0..48000).map(|i| 2f64.sqrt()*(TAU*1000.0*i as f64/FS).sin())
+            .collect();
+        let l = slm.process(&x);
+
+        assert!((l.last().unwrap() - 94.0).abs() < 0.1);
+        assert!((slm.equivalent_level() - 94.0).abs() < 0.1);
+    }
+}
@@ -6,14 +6,31 @@ use option_trait::Maybe;
 
 use array_math::SliceMath;
 
-use crate::{util::Chain, quantities::{Polynomial, ProductSequence}, System, systems::Zpk};
+use crate::{gen::filter::attenuation_frequency, util::Chain, quantities::{Polynomial, ProductSequence}, System, systems::Zpk};
+
+/// Normalization of the Bessel-Thomson prototype.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BesselNorm
+{
+    /// Asymptotic phase response matches the Butterworth prototype of the same order.
+    Phase,
+    /// Unit group delay at DC.
+    Delay,
+    /// -3 dB at a frequency of 1 rad/s.
+    Magnitude
+}
 
 pub trait BesselAP<O>: System + Sized
 where
     Self::Set: Float,
     O: Maybe<usize>
 {
-    fn besselap(order: O) -> Self;
+    fn besselap(order: O) -> Self
+    {
+        Self::besselap_norm(order, BesselNorm::Phase)
+    }
+
+    fn besselap_norm(order: O, norm: BesselNorm) -> Self;
 }
 
 impl<T> BesselAP<usize> for Zpk<Complex<T>, (), Vec<Complex<T>>, T>
@@ -21,7 +38,7 @@ where
     T: Float + FloatConst + AddAssign + MulAssign + Into<Complex<T>> + ComplexFloat<Real = T> + ndarray_linalg::Lapack<Complex = Complex<T>>,
     Complex<T>: From<T> + AddAssign + SubAssign + MulAssign + DivAssign + DivAssign<T>,
 {
-    fn besselap(order: usize) -> Self
+    fn besselap_norm(order: usize, norm: BesselNorm) -> Self
     {
         if order == 0
         {
@@ -42,33 +59,50 @@ where
             p1 = px + py;
         }
 
-        let l = p1.len();
-        let w = *p1.last().unwrap();
-        for (i, p) in p1.iter_mut()
-            .enumerate()
+        if norm != BesselNorm::Delay
+        {
+            let l = p1.len();
+            let w = *p1.last().unwrap();
+            for (i, p) in p1.iter_mut()
+                .enumerate()
+            {
+                let j = l - i - 1;
+                *p *= Float::powf(w, <T as NumCast>::from(j).unwrap()/NumCast::from(l - 1).unwrap())
+            }
+        }
+
+        let mut p: Vec<Complex<T>> = p1.rpolynomial_roots();
+
+        if norm == BesselNorm::Magnitude
         {
-            let j = l - i - 1;
-            //*p *= w;
-            *p *= Float::powf(w, <T as NumCast>::from(j).unwrap()/NumCast::from(l - 1).unwrap())
+            let wc = attenuation_frequency(&p, (T::one() + T::one()).recip());
+            for p in p.iter_mut()
+            {
+                *p /= wc
+            }
         }
 
-        let p = p1.rpolynomial_roots();
+        let k = p.iter()
+            .map(|&p| -p)
+            .product::<Complex<T>>()
+            .re;
 
         Zpk {
             z: ProductSequence::new(()),
             p: ProductSequence::new(p),
-            k: T::one()
+            k
         }
     }
 }
+
 impl<T, const N: usize> BesselAP<()> for Zpk<Complex<T>, (), [Complex<T>; N], T>
 where
     T: Float + FloatConst,
     Zpk<Complex<T>, (), Vec<Complex<T>>, T>: BesselAP<usize> + System<Set = T>
 {
-    fn besselap((): ()) -> Self
+    fn besselap_norm((): (), norm: BesselNorm) -> Self
     {
-        let Zpk { z, p, k } = Zpk::besselap(N);
+        let Zpk { z, p, k } = Zpk::besselap_norm(N, norm);
 
         Zpk {
             z,
@@ -85,7 +119,7 @@ mod test
     use linspace::LinspaceArray;
     use num::Complex;
 
-    use crate::{plot, gen::filter::BesselAP, transforms::domain::Bilinear, analysis::{FreqS, RealFreqZ}, systems::Zpk, Plane};
+    use crate::{plot, gen::filter::{BesselAP, BesselNorm}, transforms::domain::Bilinear, analysis::{FreqS, RealFreqZ}, systems::Zpk, Plane};
 
     #[test]
     fn test()
@@ -114,4 +148,36 @@ mod test
         plot::plot_curves("H(e^jw)", "plots/h_z_besselap.png", [&w.zip(h_f.map(|h| h.norm())), &w.zip(h_f.map(|h| h.arg()))])
             .unwrap();
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_norm()
+    {
+        const ORDER: usize = 6;
+
+        // Unit group delay at DC, which is the sum of -1/p over the poles.
+        let h: Zpk<Complex<f64>, (), Vec<_>, f64> = Zpk::besselap_norm(ORDER, BesselNorm::Delay);
+        let tau: f64 = h.p.iter()
+            .map(|&p| (-1.0/p).re)
+            .sum();
+        assert!((tau - 1.0).abs() < 1e-9);
+
+        // -3 dB at 1 rad/s.
+        let h: Zpk<Complex<f64>, (), Vec<_>, f64> = Zpk::besselap_norm(ORDER, BesselNorm::Magnitude);
+        let g = h.k/h.p.iter()
+            .map(|&p| (Complex::new(0.0, 1.0) - p).norm())
+            .product::<f64>();
+        assert!((g - 0.5f64.sqrt()).abs() < 1e-6);
+
+        // The normalizations only scale the poles, and the default is the phase normalization.
+        let phase: Zpk<Complex<f64>, (), Vec<_>, f64> = Zpk::besselap_norm(ORDER, BesselNorm::Phase);
+        let default: Zpk<Complex<f64>, (), [_; ORDER], f64> = Zpk::besselap(());
+        let scale = h.p[0].norm()/phase.p[0].norm();
+        for ((&p, &q), &r) in phase.p.iter()
+            .zip(h.p.iter())
+            .zip(default.p.iter())
+        {
+            assert!((p*scale - q).norm() < 1e-9);
+            assert!((p - r).norm() < 1e-12);
+        }
+    }
+}
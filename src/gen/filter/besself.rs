@@ -2,7 +2,7 @@ use ndarray::prelude::Array2;
 use num::{traits::FloatConst, Complex, Float};
 use option_trait::Maybe;
 
-use crate::{gen::filter::{BesselAP, FilterGenError, FilterGenPlane, FilterGenType}, transforms::{domain::Bilinear, filter::SfTrans, system::{ToSos, ToSs, ToTf}}, quantities::MaybeList, systems::{Sos, Ss, SsAMatrix, SsBMatrix, SsCMatrix, SsDMatrix, Tf, Zpk}, System};
+use crate::{gen::filter::{BesselAP, BesselNorm, FilterGenError, FilterGenPlane, FilterGenType}, transforms::{domain::Bilinear, filter::SfTrans, system::{ToSos, ToSs, ToTf}}, quantities::MaybeList, systems::{Sos, Ss, SsAMatrix, SsBMatrix, SsCMatrix, SsDMatrix, Tf, Zpk}, System};
 
 pub trait BesselF<O>: System + Sized
 where
@@ -15,6 +15,20 @@ where
         filter_type: FilterGenType,
         plane: FilterGenPlane<Self::Set>
     ) -> Result<Self, FilterGenError>
+    where
+        [(); F - 1]:,
+        [(); 2 - F]:
+    {
+        Self::besself_norm(order, frequencies, filter_type, BesselNorm::Phase, plane)
+    }
+
+    fn besself_norm<const F: usize>(
+        order: O,
+        frequencies: [Self::Set; F],
+        filter_type: FilterGenType,
+        norm: BesselNorm,
+        plane: FilterGenPlane<Self::Set>
+    ) -> Result<Self, FilterGenError>
     where
         [(); F - 1]:,
         [(); 2 - F]:;
@@ -28,10 +42,11 @@ where
     Zpk<Complex<T>, (), P, T>: BesselAP<usize> + SfTrans<1, Output = Self> + SfTrans<2, Output = Self> + System<Set = T>,
     Self: Bilinear<Output = Self> + System<Set = T>
 {
-    fn besself<const F: usize>(
+    fn besself_norm<const F: usize>(
         order: usize,
         mut frequencies: [T; F],
         filter_type: FilterGenType,
+        norm: BesselNorm,
         plane: FilterGenPlane<T>
     ) -> Result<Self, FilterGenError>
     where
@@ -80,7 +95,7 @@ where
             None
         };
 
-        let zpk = Zpk::besselap(order);
+        let zpk = Zpk::besselap_norm(order, norm);
         
         let zpk = if !band && F == 2
         {
@@ -115,17 +130,18 @@ where
     T: Float + FloatConst,
     Zpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T>: BesselF<usize> + ToTf<T, Vec<T>, Vec<T>, (), ()> + System<Set = T>
 {
-    fn besself<const F: usize>(
+    fn besself_norm<const F: usize>(
         order: usize,
         frequencies: [T; F],
         filter_type: FilterGenType,
+        norm: BesselNorm,
         plane: FilterGenPlane<T>
     ) -> Result<Self, FilterGenError>
     where
         [(); F - 1]:,
         [(); 2 - F]:
     {
-        let zpk = Zpk::besself(order, frequencies, filter_type, plane)?;
+        let zpk = Zpk::besself_norm(order, frequencies, filter_type, norm, plane)?;
     
         Ok(zpk.to_tf((), ()))
     }
@@ -137,17 +153,18 @@ where
     T: Float + FloatConst,
     Tf<T, Vec<T>, Vec<T>>: BesselF<usize> + System<Set = T>
 {
-    fn besself<const F: usize>(
+    fn besself_norm<const F: usize>(
         (): (),
         frequencies: [T; F],
         filter_type: FilterGenType,
+        norm: BesselNorm,
         plane: FilterGenPlane<T>
     ) -> Result<Self, FilterGenError>
     where
         [(); F - 1]:,
         [(); 2 - F]:
     {
-        let tf = Tf::besself(N - 1, frequencies, filter_type, plane)?;
+        let tf = Tf::besself_norm(N - 1, frequencies, filter_type, norm, plane)?;
 
         Ok(tf.truncate())
     }
@@ -158,17 +175,18 @@ where
     T: Float + FloatConst,
     Zpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T>: BesselF<usize> + ToSos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>, (), ()> + System<Set = T>
 {
-    fn besself<const F: usize>(
+    fn besself_norm<const F: usize>(
         order: usize,
         frequencies: [T; F],
         filter_type: FilterGenType,
+        norm: BesselNorm,
         plane: FilterGenPlane<T>
     ) -> Result<Self, FilterGenError>
     where
         [(); F - 1]:,
         [(); 2 - F]:
     {
-        let zpk = Zpk::besself(order, frequencies, filter_type, plane)?;
+        let zpk = Zpk::besself_norm(order, frequencies, filter_type, norm, plane)?;
     
         Ok(zpk.to_sos((), ()))
     }
@@ -179,17 +197,18 @@ where
     T: Float + FloatConst,
     Sos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>>: BesselF<usize> + System<Set = T>
 {
-    fn besself<const F: usize>(
+    fn besself_norm<const F: usize>(
         (): (),
         frequencies: [T; F],
         filter_type: FilterGenType,
+        norm: BesselNorm,
         plane: FilterGenPlane<T>
     ) -> Result<Self, FilterGenError>
     where
         [(); F - 1]:,
         [(); 2 - F]:
     {
-        let sos = Sos::besself(N*2, frequencies, filter_type, plane)?;
+        let sos = Sos::besself_norm(N*2, frequencies, filter_type, norm, plane)?;
 
         Ok(Sos {
             sos: sos.sos.try_into().map_err(|_| ()).unwrap()
@@ -203,17 +222,18 @@ where
     Zpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T>: BesselF<usize> + ToSs<T, Array2<T>, Array2<T>, Array2<T>, Array2<T>> + System<Set = T>,
     Array2<T>: SsAMatrix<T, Array2<T>, Array2<T>, Array2<T>> + SsBMatrix<T, Array2<T>, Array2<T>, Array2<T>> + SsCMatrix<T, Array2<T>, Array2<T>, Array2<T>>+ SsDMatrix<T, Array2<T>, Array2<T>, Array2<T>>
 {
-    fn besself<const F: usize>(
+    fn besself_norm<const F: usize>(
         order: usize,
         frequencies: [T; F],
         filter_type: FilterGenType,
+        norm: BesselNorm,
         plane: FilterGenPlane<T>
     ) -> Result<Self, FilterGenError>
     where
         [(); F - 1]:,
         [(); 2 - F]:
     {
-        let zpk = Zpk::besself(order, frequencies, filter_type, plane)?;
+        let zpk = Zpk::besself_norm(order, frequencies, filter_type, norm, plane)?;
     
         Ok(zpk.to_ss())
     }
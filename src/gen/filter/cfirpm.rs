@@ -0,0 +1,416 @@
+use core::{iter::Sum, ops::{AddAssign, MulAssign}};
+
+use array_math::SliceMath;
+use ndarray::{Array1, Array2};
+use ndarray_linalg::{Lapack, Solve};
+use num::{complex::ComplexFloat, traits::FloatConst, Complex, Float, NumCast, Zero};
+use option_trait::Maybe;
+use thiserror::Error;
+
+use crate::{quantities::Polynomial, System, systems::Tf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum CFirPmError
+{
+    #[error("Filter order must be at least 1.")]
+    ZeroOrder,
+    #[error("Bands must be monotonic.")]
+    EdgesNotNondecreasing,
+    #[error("Band edges must be within ±1/2 the sampling frequency, or if no sampling frequency is specified, between -1 and 1.")]
+    EdgesOutOfRange,
+    #[error("Band edges are too close together.")]
+    BandTooNarrow,
+    #[error("Weighting function out-of-range.")]
+    WeightsOutOfRange,
+    #[error("Sampling frequency must be a positive number.")]
+    InvalidSamplingFrequency,
+    #[error("Numerical error.")]
+    NumericalError
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CFirPmPhase
+{
+    /// The response is delayed by half the filter order.
+    Linear,
+    /// The response is approximated as given.
+    Arbitrary,
+    /// The minimum-phase response with the given magnitude is approximated, and any zeros left outside the unit circle are
+    /// reflected inside. The returned error is then that of the magnitude response.
+    Minimum
+}
+
+pub trait CFirPm: System + Sized
+{
+    fn cfirpm<FS, const B2: usize>(
+        order: usize,
+        bands: [<Self::Set as ComplexFloat>::Real; B2],
+        response: [Self::Set; B2],
+        weight: [<Self::Set as ComplexFloat>::Real; B2/2],
+        phase: CFirPmPhase,
+        sampling_frequency: FS
+    ) -> Result<(Self, <Self::Set as ComplexFloat>::Real), CFirPmError>
+    where
+        FS: Maybe<<Self::Set as ComplexFloat>::Real>,
+        [(); 0 - B2 % 2]:,
+        [(); B2/2 - 1]:;
+}
+
+impl<T> CFirPm for Tf<Complex<T>, Vec<Complex<T>>, ()>
+where
+    T: Float + FloatConst + Lapack<Real = T, Complex = Complex<T>>,
+    Complex<T>: Lapack<Real = T> + MulAssign + AddAssign + MulAssign<T> + Sum
+{
+    fn cfirpm<FS, const B2: usize>(
+        order: usize,
+        mut bands: [T; B2],
+        response: [Complex<T>; B2],
+        weight: [T; B2/2],
+        phase: CFirPmPhase,
+        sampling_frequency: FS
+    ) -> Result<(Self, T), CFirPmError>
+    where
+        FS: Maybe<T>,
+        [(); 0 - B2 % 2]:,
+        [(); B2/2 - 1]:
+    {
+        let zero = T::zero();
+        let one = T::one();
+        let two = one + one;
+        let half = two.recip();
+
+        if order < 1
+        {
+            return Err(CFirPmError::ZeroOrder)
+        }
+        if let Some(fs) = sampling_frequency.into_option()
+        {
+            if !(fs > zero) || !Float::is_finite(fs)
+            {
+                return Err(CFirPmError::InvalidSamplingFrequency)
+            }
+            for f in bands.iter_mut()
+            {
+                *f = *f/(fs*half)
+            }
+        }
+        if !bands.is_sorted()
+        {
+            return Err(CFirPmError::EdgesNotNondecreasing)
+        }
+        if bands[0] < -one || bands[B2 - 1] > one
+        {
+            return Err(CFirPmError::EdgesOutOfRange)
+        }
+        if bands.chunks(2)
+            .any(|b| !(b[1] - b[0] > T::epsilon()))
+        {
+            return Err(CFirPmError::BandTooNarrow)
+        }
+        if weight.iter()
+            .any(|&w| !(w > zero) || !Float::is_finite(w))
+        {
+            return Err(CFirPmError::WeightsOutOfRange)
+        }
+
+        let nb = B2/2;
+        let m = order + 1;
+        let total_width = bands.chunks(2)
+            .map(|b| b[1] - b[0])
+            .fold(zero, |a, b| a + b);
+        let density = <T as NumCast>::from(16*m).unwrap();
+
+        let mut f = vec![];
+        let mut d = vec![];
+        let mut wt = vec![];
+        for i in 0..nb
+        {
+            let (f0, f1) = (bands[2*i], bands[2*i + 1]);
+            let (r0, r1) = (response[2*i], response[2*i + 1]);
+            let l = <usize as NumCast>::from(Float::ceil(density*(f1 - f0)/total_width)).unwrap().max(4);
+            let lf = <T as NumCast>::from(l - 1).unwrap();
+            for j in 0..l
+            {
+                let x = <T as NumCast>::from(j).unwrap()/lf;
+                f.push(f0 + (f1 - f0)*x);
+                d.push(r0 + (r1 - r0)*x);
+                wt.push(weight[i]);
+            }
+        }
+        let l = f.len();
+
+        match phase
+        {
+            CFirPmPhase::Linear => {
+                let delay = <T as NumCast>::from(order).unwrap()*half;
+                for (d, &f) in d.iter_mut()
+                    .zip(f.iter())
+                {
+                    *d = *d*Complex::cis(-T::PI()*f*delay)
+                }
+            },
+            CFirPmPhase::Arbitrary => (),
+            CFirPmPhase::Minimum => {
+                // Homomorphic construction of the minimum-phase response belonging to the desired magnitude.
+                let nfft = (8*l).next_power_of_two();
+                let nfftf = <T as NumCast>::from(nfft).unwrap();
+                let mag_max = d.iter()
+                    .map(|d| d.norm())
+                    .fold(zero, Float::max);
+                let floor = mag_max*<T as NumCast>::from(1e-5).unwrap();
+                let mut edges: Vec<(T, T)> = f.iter()
+                    .zip(d.iter())
+                    .map(|(&f, d)| (f, d.norm()))
+                    .collect();
+                edges.push((edges[0].0 + two, edges[0].1));
+                let magnitude = |mut x: T| {
+                    if x < edges[0].0
+                    {
+                        x = x + two
+                    }
+                    let i = edges.iter()
+                        .take_while(|e| e.0 <= x)
+                        .count()
+                        .max(1)
+                        .min(edges.len() - 1);
+                    let (f0, a0) = edges[i - 1];
+                    let (f1, a1) = edges[i];
+                    let a = if f1 > f0 {a0 + (a1 - a0)*(x - f0)/(f1 - f0)} else {a0};
+                    Float::max(a, floor)
+                };
+                let mut c: Vec<Complex<T>> = (0..nfft).map(|k| {
+                        let mut x = two*<T as NumCast>::from(k).unwrap()/nfftf;
+                        if x >= one
+                        {
+                            x = x - two
+                        }
+                        Complex::from(Float::ln(magnitude(x)))
+                    }).collect();
+                c.ifft();
+                for (k, c) in c.iter_mut()
+                    .enumerate()
+                {
+                    if k > 0 && k < nfft/2
+                    {
+                        *c = *c*two
+                    }
+                    else if k > nfft/2
+                    {
+                        *c = Complex::zero()
+                    }
+                }
+                c.fft();
+                for c in c.iter_mut()
+                {
+                    *c = c.exp()
+                }
+                for (d, &f) in d.iter_mut()
+                    .zip(f.iter())
+                {
+                    let mut x = f*half*nfftf;
+                    if x < zero
+                    {
+                        x = x + nfftf
+                    }
+                    let k0 = <usize as NumCast>::from(Float::floor(x)).unwrap() % nfft;
+                    let k1 = (k0 + 1) % nfft;
+                    let frac = x - Float::floor(x);
+                    *d = c[k0] + (c[k1] - c[k0])*frac
+                }
+            }
+        }
+
+        let cmat = Array2::from_shape_fn((l, m), |(j, k)| Complex::cis(-T::PI()*f[j]*<T as NumCast>::from(k).unwrap()));
+        let d = Array1::from_vec(d);
+
+        // Lawson's algorithm: iteratively reweighted least squares converging to the complex Chebyshev solution.
+        let mut v = vec![one/<T as NumCast>::from(l).unwrap(); l];
+        let mut h = Array1::from_elem(m, Complex::zero());
+        let mut e_max = T::infinity();
+        for _ in 0..256
+        {
+            // The error is weighted by wt, so the least-squares weights are squared to minimize the weighted Chebyshev error.
+            let cv = Array2::from_shape_fn((m, l), |(k, j)| cmat[[j, k]].conj()*(v[j]*wt[j]*wt[j]));
+            h = cv.dot(&cmat)
+                .solve(&cv.dot(&d))
+                .map_err(|_| CFirPmError::NumericalError)?;
+
+            let e: Vec<T> = (cmat.dot(&h) - &d).iter()
+                .zip(wt.iter())
+                .map(|(e, &w)| e.norm()*w)
+                .collect();
+            let e_max_next = e.iter()
+                .copied()
+                .fold(zero, Float::max);
+            let converged = Float::abs(e_max - e_max_next) <= e_max_next*<T as NumCast>::from(1e-6).unwrap();
+            e_max = e_max_next;
+            if converged
+            {
+                break
+            }
+
+            let s = v.iter()
+                .zip(e.iter())
+                .map(|(&v, &e)| v*e)
+                .fold(zero, |a, b| a + b);
+            if !(s > zero)
+            {
+                break
+            }
+            for (v, &e) in v.iter_mut()
+                .zip(e.iter())
+            {
+                *v = *v*e/s
+            }
+        }
+
+        let mut h = h.to_vec();
+        if phase == CFirPmPhase::Minimum && !h[0].is_zero()
+        {
+            // The approximation may still have zeros outside the unit circle. Reflecting a zero to its conjugate reciprocal keeps
+            // the magnitude response, up to a gain of the zero's radius.
+            let mut k = h[0];
+            let z: Vec<Complex<T>> = h.rpolynomial_roots();
+            h = z.into_iter()
+                .map(|z| {
+                    let r = z.norm();
+                    let z = if r > one
+                    {
+                        k = k*r;
+                        z.conj()
+                            .inv()
+                    }
+                    else
+                    {
+                        z
+                    };
+                    Polynomial::new([Complex::from(one), -z])
+                }).product::<Polynomial<_, Vec<_>>>()
+                .into_inner()
+                .into_iter()
+                .map(|h| h*k)
+                .collect();
+
+            e_max = cmat.dot(&Array1::from_vec(h.clone())).iter()
+                .zip(d.iter())
+                .zip(wt.iter())
+                .map(|((h, d), &w)| Float::abs(h.norm() - d.norm())*w)
+                .fold(zero, Float::max);
+        }
+
+        Ok((Tf::new(h, ()), e_max))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::PI;
+
+    use array_math::ArrayOps;
+    use num::Complex;
+
+    use crate::{plot, gen::filter::{CFirPm, CFirPmPhase}, analysis::FreqZ, systems::{Tf, Zpk}, transforms::system::ToZpk};
+
+    #[test]
+    fn test()
+    {
+        let one = Complex::new(1.0, 0.0);
+        let zero = Complex::new(0.0, 0.0);
+        let bands = [-1.0, -0.5, -0.4, 0.7, 0.8, 1.0];
+
+        // Largest deviation of the magnitude response from the desired magnitude within each band.
+        let ripples = |h: &Tf<Complex<f64>, Vec<Complex<f64>>, ()>| -> Vec<f64> {
+            bands.chunks(2)
+                .zip([0.0, 1.0, 0.0])
+                .map(|(band, d)| (0..=200).map(|i| {
+                        let f = band[0] + (band[1] - band[0])*i as f64/200.0;
+                        let h: Complex<f64> = h.b.iter()
+                            .enumerate()
+                            .map(|(k, &h)| h*Complex::cis(-PI*f*k as f64))
+                            .sum();
+                        (h.norm() - d).abs()
+                    }).fold(0.0, f64::max)
+                ).collect()
+        };
+
+        let (h, e) = Tf::<Complex<f64>, Vec<_>, ()>::cfirpm(
+            30,
+            bands,
+            [zero, zero, one, one, zero, zero],
+            [1.0, 1.0, 1.0],
+            CFirPmPhase::Linear,
+            ()
+        ).unwrap();
+
+        // With equal weights, all bands share the same ripple, which is the returned error.
+        let r = ripples(&h);
+        assert!(e > 0.0 && e < 0.1);
+        for r in r
+        {
+            assert!(r <= e*1.05 && r >= e*0.5, "ripple {} for error {}", r, e);
+        }
+
+        const N: usize = 1024;
+        let (h_f, w): ([_; N], _) = h.freqz((), true);
+
+        plot::plot_curves("H(e^jw)", "plots/h_z_cfirpm.png", [&w.zip(h_f.map(|h| h.norm()))])
+            .unwrap();
+
+        // With the passband weighted ten times heavier, its ripple is a tenth of that of the stopbands.
+        let (h, e) = Tf::<Complex<f64>, Vec<_>, ()>::cfirpm(
+            30,
+            bands,
+            [zero, zero, one, one, zero, zero],
+            [1.0, 10.0, 1.0],
+            CFirPmPhase::Linear,
+            ()
+        ).unwrap();
+        let r = ripples(&h);
+        for (r, w) in r.iter()
+            .zip([1.0, 10.0, 1.0])
+        {
+            assert!(r*w <= e*1.05 && r*w >= e*0.5, "weighted ripple {} for error {}", r*w, e);
+        }
+
+        // A zero-phase response cannot be followed closely by a causal filter, but the magnitude still ripples evenly.
+        let (h, e) = Tf::<Complex<f64>, Vec<_>, ()>::cfirpm(
+            30,
+            bands,
+            [zero, zero, one, one, zero, zero],
+            [1.0, 1.0, 1.0],
+            CFirPmPhase::Arbitrary,
+            ()
+        ).unwrap();
+        assert!(e > 0.0 && e < 0.4);
+        for r in ripples(&h)
+        {
+            assert!(r <= e*1.05 && r >= e*0.5, "ripple {} for error {}", r, e);
+        }
+
+        // The minimum-phase design ripples as the linear-phase one does, with all of its zeros on or inside the unit circle.
+        for weight in [[1.0, 1.0, 1.0], [1.0, 10.0, 1.0]]
+        {
+            let (h, e) = Tf::<Complex<f64>, Vec<_>, ()>::cfirpm(
+                30,
+                bands,
+                [zero, zero, one, one, zero, zero],
+                weight,
+                CFirPmPhase::Minimum,
+                ()
+            ).unwrap();
+            assert!(e > 0.0 && e < 0.2);
+            for (r, w) in ripples(&h).into_iter()
+                .zip(weight)
+            {
+                assert!(r*w <= e*1.05 && r*w >= e*0.5, "weighted ripple {} for error {}", r*w, e);
+            }
+
+            let h: Zpk<_, Vec<_>, Vec<_>, _> = h.to_zpk((), ());
+            for z in h.z.to_vec()
+            {
+                assert!(z.norm() <= 1.0 + 1e-6, "zero {} outside the unit circle", z);
+            }
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use ndarray::{Array1, Array2};
+use ndarray_linalg::{Inverse, Lapack};
+use num::{traits::FloatConst, Float, NumCast};
+use option_trait::Maybe;
+use thiserror::Error;
+
+use crate::{System, systems::Tf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum FirClsError
+{
+    #[error("Filter order must be at least 1.")]
+    ZeroOrder,
+    #[error("Band edges must be monotonic starting at zero.")]
+    EdgesNotNondecreasing,
+    #[error("Band edges must start at zero and end at 1/2 the sampling frequency, or if no sampling frequency is specified, end at 1.")]
+    EdgesOutOfRange,
+    #[error("Sampling frequency must be a positive number.")]
+    InvalidSamplingFrequency,
+    #[error("Each band's lower bound must not exceed its amplitude, and its amplitude must not exceed its upper bound.")]
+    BoundsInconsistent,
+    #[error("Only low-pass and high-pass filters can be designed this way.")]
+    InvalidFilterType,
+    #[error("Numerical error.")]
+    NumericalError,
+    #[error("The response did not settle within the bounds.")]
+    NoConvergence
+}
+
+pub trait FirCls<O>: System + Sized
+where
+    Self::Set: Float,
+    O: Maybe<usize>
+{
+    fn fircls<FS, const F: usize>(
+        order: O,
+        frequencies: [Self::Set; F],
+        amplitudes: [Self::Set; F - 1],
+        upper: [Self::Set; F - 1],
+        lower: [Self::Set; F - 1],
+        sampling_frequency: FS
+    ) -> Result<Self, FirClsError>
+    where
+        FS: Maybe<Self::Set>,
+        [(); F - 2]:;
+}
+
+impl<T> FirCls<usize> for Tf<T, Vec<T>, ()>
+where
+    T: Float + FloatConst + Lapack<Real = T>
+{
+    fn fircls<FS, const F: usize>(
+        order: usize,
+        mut frequencies: [T; F],
+        amplitudes: [T; F - 1],
+        upper: [T; F - 1],
+        lower: [T; F - 1],
+        sampling_frequency: FS
+    ) -> Result<Self, FirClsError>
+    where
+        FS: Maybe<T>,
+        [(); F - 2]:
+    {
+        let zero = T::zero();
+        let one = T::one();
+        let two = one + one;
+        let half = two.recip();
+
+        if order < 1
+        {
+            return Err(FirClsError::ZeroOrder)
+        }
+        if let Some(fs) = sampling_frequency.into_option()
+        {
+            if !(fs > zero) || !Float::is_finite(fs)
+            {
+                return Err(FirClsError::InvalidSamplingFrequency)
+            }
+            for f in frequencies.iter_mut()
+            {
+                *f = *f/(fs*half)
+            }
+        }
+        if !frequencies.is_sorted()
+        {
+            return Err(FirClsError::EdgesNotNondecreasing)
+        }
+        if frequencies[0] != zero || frequencies[F - 1] != one
+        {
+            return Err(FirClsError::EdgesOutOfRange)
+        }
+        if amplitudes.iter()
+            .zip(upper.iter())
+            .zip(lower.iter())
+            .any(|((a, u), l)| !(l <= a && a <= u))
+        {
+            return Err(FirClsError::BoundsInconsistent)
+        }
+
+        let odd = order % 2 == 1;
+        let k = order/2 + 1;
+        let delta = if odd {half} else {zero};
+        let l = (16*(order + 1)).max(512);
+        let lf = <T as NumCast>::from(l - 1).unwrap();
+
+        let basis = |w: T, i: usize| Float::cos((<T as NumCast>::from(i).unwrap() + delta)*w);
+
+        let w: Vec<T> = (0..l).map(|j| T::PI()*<T as NumCast>::from(j).unwrap()/lf)
+            .collect();
+        let band = |f: T| frequencies[1..F - 1].iter()
+            .take_while(|&&e| e < f)
+            .count();
+        let (d, (u, lo)): (Vec<T>, (Vec<T>, Vec<T>)) = w.iter()
+            .map(|&w| {
+                let f = w/T::PI();
+                let i = band(f);
+                if i + 1 < F - 1 && frequencies[i + 1] == f
+                {
+                    (
+                        (amplitudes[i] + amplitudes[i + 1])*half,
+                        (Float::max(upper[i], upper[i + 1]), Float::min(lower[i], lower[i + 1]))
+                    )
+                }
+                else
+                {
+                    (amplitudes[i], (upper[i], lower[i]))
+                }
+            }).unzip();
+
+        let c = Array2::from_shape_fn((l, k), |(j, i)| basis(w[j], i));
+        let lff = <T as NumCast>::from(l).unwrap();
+        let r = c.t().dot(&c).mapv(|r| r/lff);
+        let p = c.t().dot(&Array1::from_vec(d)).mapv(|p| p/lff);
+        let rinv = r.inv()
+            .map_err(|_| FirClsError::NumericalError)?;
+        let rinvp = rinv.dot(&p);
+
+        let tol = Float::sqrt(T::epsilon());
+        let mut a = rinvp.clone();
+        let mut converged = false;
+
+        for _ in 0..256
+        {
+            let y = c.dot(&a);
+
+            // Local extrema that touch or violate the bounds make up the next constraint set. The bounds only apply at the
+            // extrema, so the response may pass through them monotonically at the band edges.
+            let mut s: Vec<(usize, T, bool)> = vec![];
+            let mut violated = false;
+            for j in 0..l
+            {
+                let prev = if j > 0 {Some(y[j - 1])} else {None};
+                let next = y.get(j + 1).copied();
+                let is_max = prev.map(|p| y[j] >= p).unwrap_or(true) && next.map(|n| y[j] >= n).unwrap_or(true);
+                let is_min = prev.map(|p| y[j] <= p).unwrap_or(true) && next.map(|n| y[j] <= n).unwrap_or(true);
+                if is_max && y[j] >= u[j] - tol
+                {
+                    violated |= y[j] > u[j] + tol;
+                    s.push((j, u[j], true))
+                }
+                else if is_min && y[j] <= lo[j] + tol
+                {
+                    violated |= y[j] < lo[j] - tol;
+                    s.push((j, lo[j], false))
+                }
+            }
+
+            if !violated
+            {
+                converged = true;
+                break
+            }
+
+            loop
+            {
+                if s.is_empty()
+                {
+                    a = rinvp.clone();
+                    break
+                }
+                let g = Array2::from_shape_fn((s.len(), k), |(m, i)| basis(w[s[m].0], i));
+                let b = Array1::from_shape_fn(s.len(), |m| s[m].1);
+                let grinv = g.dot(&rinv);
+                let mu = grinv.dot(&g.t())
+                    .inv()
+                    .map_err(|_| FirClsError::NumericalError)?
+                    .dot(&(g.dot(&rinvp) - b));
+
+                // Multipliers of the wrong sign mean the constraint is not active at the optimum.
+                if let Some((m, _)) = mu.iter()
+                    .zip(s.iter())
+                    .map(|(&mu, &(_, _, upper))| if upper {mu} else {-mu})
+                    .enumerate()
+                    .filter(|&(_, mu)| mu < zero)
+                    .reduce(|a, b| if b.1 < a.1 {b} else {a})
+                {
+                    s.remove(m);
+                    continue
+                }
+
+                a = rinv.dot(&(&p - &g.t().dot(&mu)));
+                break
+            }
+        }
+        if !converged
+        {
+            return Err(FirClsError::NoConvergence)
+        }
+
+        let mut h = vec![zero; order + 1];
+        if odd
+        {
+            let m = (order - 1)/2;
+            for (i, &a) in a.iter()
+                .enumerate()
+            {
+                h[m - i] = a*half;
+                h[m + 1 + i] = a*half;
+            }
+        }
+        else
+        {
+            let m = order/2;
+            h[m] = a[0];
+            for (i, &a) in a.iter()
+                .enumerate()
+                .skip(1)
+            {
+                h[m - i] = a*half;
+                h[m + i] = a*half;
+            }
+        }
+
+        Ok(Tf::new(h, ()))
+    }
+}
+
+impl<T, const N: usize> FirCls<()> for Tf<T, [T; N], ()>
+where
+    T: Float,
+    Tf<T, Vec<T>, ()>: FirCls<usize> + System<Set = T>,
+    [(); N - 2]:
+{
+    fn fircls<FS, const F: usize>(
+        (): (),
+        frequencies: [T; F],
+        amplitudes: [T; F - 1],
+        upper: [T; F - 1],
+        lower: [T; F - 1],
+        sampling_frequency: FS
+    ) -> Result<Self, FirClsError>
+    where
+        FS: Maybe<T>,
+        [(); F - 2]:
+    {
+        let h = Tf::fircls(N - 1, frequencies, amplitudes, upper, lower, sampling_frequency)?;
+
+        Ok(Tf::new(
+            h.b.into_inner()
+                .try_into()
+                .ok()
+                .unwrap(),
+            ()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::PI;
+
+    use array_math::ArrayOps;
+
+    use crate::{plot, gen::filter::FirCls, Plane, analysis::RealFreqZ, systems::{Tf, Zpk}, transforms::system::ToZpk};
+
+    #[test]
+    fn test()
+    {
+        let frequencies = [0.0, 0.4, 0.8, 1.0];
+        let upper = [0.02, 1.02, 0.01];
+        let lower = [-0.02, 0.98, -0.01];
+
+        let h: Tf<f64, [_; 51]> = Tf::fircls(
+            (),
+            frequencies,
+            [0.0, 1.0, 0.0],
+            upper,
+            lower,
+            ()
+        ).unwrap();
+
+        // The local extrema of the zero-phase amplitude response within each band lie within its bounds.
+        let b: Vec<f64> = h.b.iter()
+            .copied()
+            .collect();
+        let amplitude = |f: f64| b.iter()
+            .enumerate()
+            .map(|(k, &b_k)| b_k*(PI*f*(k as f64 - (b.len() - 1) as f64/2.0)).cos())
+            .sum::<f64>();
+        for (band, (&u, &l)) in frequencies.windows(2)
+            .zip(upper.iter().zip(lower.iter()))
+        {
+            let a: Vec<f64> = (0..=400).map(|i| amplitude(band[0] + (band[1] - band[0])*i as f64/400.0))
+                .collect();
+            for a in a.windows(3)
+                .filter(|a| (a[1] >= a[0] && a[1] >= a[2]) || (a[1] <= a[0] && a[1] <= a[2]))
+                .map(|a| a[1])
+            {
+                assert!(a <= u + 1e-3 && a >= l - 1e-3, "amplitude {} outside [{}, {}]", a, l, u);
+            }
+        }
+
+        const N: usize = 1024;
+        let (h_f, w): ([_; N], _) = h.real_freqz(());
+
+        plot::plot_curves("H(e^jw)", "plots/h_z_fircls.png", [&w.zip(h_f.map(|h| h.norm()))])
+            .unwrap();
+
+        let h: Zpk<_, Vec<_>, Vec<_>, _> = h.to_zpk((), ());
+
+        plot::plot_pz("H(z)", "plots/pz_z_fircls.png", &h.p, &h.z, Plane::Z)
+            .unwrap();
+    }
+}
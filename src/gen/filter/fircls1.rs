@@ -0,0 +1,150 @@
+use num::{traits::FloatConst, Float};
+use option_trait::Maybe;
+
+use crate::{gen::filter::{FilterGenType, FirCls, FirClsError}, System, systems::Tf};
+
+pub trait FirCls1<O>: System + Sized
+where
+    Self::Set: Float,
+    O: Maybe<usize>
+{
+    fn fircls1<FS>(
+        order: O,
+        cutoff: Self::Set,
+        passband_deviation: Self::Set,
+        stopband_deviation: Self::Set,
+        filter_type: FilterGenType,
+        sampling_frequency: FS
+    ) -> Result<Self, FirClsError>
+    where
+        FS: Maybe<Self::Set>;
+}
+
+impl<T> FirCls1<usize> for Tf<T, Vec<T>, ()>
+where
+    T: Float + FloatConst,
+    Self: FirCls<usize> + System<Set = T>
+{
+    fn fircls1<FS>(
+        order: usize,
+        cutoff: T,
+        dp: T,
+        ds: T,
+        filter_type: FilterGenType,
+        sampling_frequency: FS
+    ) -> Result<Self, FirClsError>
+    where
+        FS: Maybe<T>
+    {
+        let zero = T::zero();
+        let one = T::one();
+        let two = one + one;
+
+        let nyq = match sampling_frequency.into_option()
+        {
+            Some(fs) => {
+                if !(fs > zero) || !fs.is_finite()
+                {
+                    return Err(FirClsError::InvalidSamplingFrequency)
+                }
+                fs/two
+            },
+            None => one
+        };
+        let (dp, ds) = (dp.abs(), ds.abs());
+
+        match filter_type
+        {
+            FilterGenType::LowPass => Self::fircls(
+                order,
+                [zero, cutoff, nyq],
+                [one, zero],
+                [one + dp, ds],
+                [one - dp, -ds],
+                nyq*two
+            ),
+            FilterGenType::HighPass => Self::fircls(
+                order,
+                [zero, cutoff, nyq],
+                [zero, one],
+                [ds, one + dp],
+                [-ds, one - dp],
+                nyq*two
+            ),
+            FilterGenType::BandPass | FilterGenType::BandStop => Err(FirClsError::InvalidFilterType)
+        }
+    }
+}
+
+impl<T, const N: usize> FirCls1<()> for Tf<T, [T; N], ()>
+where
+    T: Float + FloatConst,
+    Tf<T, Vec<T>, ()>: FirCls1<usize> + System<Set = T>,
+    [(); N - 2]:
+{
+    fn fircls1<FS>(
+        (): (),
+        cutoff: T,
+        dp: T,
+        ds: T,
+        filter_type: FilterGenType,
+        sampling_frequency: FS
+    ) -> Result<Self, FirClsError>
+    where
+        FS: Maybe<T>
+    {
+        let h = Tf::fircls1(N - 1, cutoff, dp, ds, filter_type, sampling_frequency)?;
+
+        Ok(Tf::new(
+            h.b.into_inner()
+                .try_into()
+                .ok()
+                .unwrap(),
+            ()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::PI;
+
+    use array_math::ArrayOps;
+
+    use crate::{plot, gen::filter::{FilterGenType, FirCls1}, analysis::RealFreqZ, systems::Tf};
+
+    #[test]
+    fn test()
+    {
+        let h: Tf<f64, [_; 56]> = Tf::fircls1((), 0.3, 0.02, 0.008, FilterGenType::LowPass, ())
+            .unwrap();
+
+        // The local extrema of the zero-phase amplitude response lie within the passband and stopband bounds.
+        let b: Vec<f64> = h.b.iter()
+            .copied()
+            .collect();
+        let amplitude = |f: f64| b.iter()
+            .enumerate()
+            .map(|(k, &b_k)| b_k*(PI*f*(k as f64 - (b.len() - 1) as f64/2.0)).cos())
+            .sum::<f64>();
+        for (band, (u, l)) in [[0.0, 0.3], [0.3, 1.0]].into_iter()
+            .zip([(1.02, 0.98), (0.008, -0.008)])
+        {
+            let a: Vec<f64> = (0..=400).map(|i| amplitude(band[0] + (band[1] - band[0])*i as f64/400.0))
+                .collect();
+            for a in a.windows(3)
+                .filter(|a| (a[1] >= a[0] && a[1] >= a[2]) || (a[1] <= a[0] && a[1] <= a[2]))
+                .map(|a| a[1])
+            {
+                assert!(a <= u + 1e-3 && a >= l - 1e-3, "amplitude {} outside [{}, {}]", a, l, u);
+            }
+        }
+
+        const N: usize = 1024;
+        let (h_f, w): ([_; N], _) = h.real_freqz(());
+
+        plot::plot_curves("H(e^jw)", "plots/h_z_fircls1.png", [&w.zip(h_f.map(|h| h.norm()))])
+            .unwrap();
+    }
+}
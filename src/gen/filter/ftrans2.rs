@@ -0,0 +1,162 @@
+use ndarray::Array2;
+use num::Float;
+use option_trait::Maybe;
+use thiserror::Error;
+
+use crate::{quantities::List, systems::Tf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum FTrans2Error
+{
+    #[error("Prototype filter must be odd-length and symmetric (zero-phase).")]
+    PrototypeNotSymmetric,
+    #[error("Transformation matrix must have odd dimensions.")]
+    TransformationNotOdd
+}
+
+/// McClellan frequency transformation of a 1-D zero-phase FIR prototype into a 2-D FIR kernel.
+///
+/// The 1-D response H(w) is mapped to H(w1, w2) = H(w), where cos(w) = T(w1, w2) is the response of the transformation matrix.
+/// If no transformation matrix is given, the McClellan transformation is used, which gives nearly circularly symmetric contours.
+/// The kernel can be applied with [Conv2d](crate::operations::convolution::Conv2d).
+pub trait FTrans2<T, B, TT>: Sized
+where
+    T: Float,
+    B: List<T>,
+    TT: Maybe<Array2<T>>
+{
+    fn ftrans2(prototype: Tf<T, B, ()>, transformation: TT) -> Result<Self, FTrans2Error>;
+}
+
+impl<T, B, TT> FTrans2<T, B, TT> for Array2<T>
+where
+    T: Float,
+    B: List<T>,
+    TT: Maybe<Array2<T>>
+{
+    fn ftrans2(prototype: Tf<T, B, ()>, transformation: TT) -> Result<Self, FTrans2Error>
+    {
+        let zero = T::zero();
+        let one = T::one();
+        let two = one + one;
+
+        let b = prototype.b.into_inner();
+        let b = b.as_view_slice();
+        let tol = b.iter()
+            .map(|&b| Float::abs(b))
+            .fold(zero, Float::max)*Float::sqrt(T::epsilon());
+        if b.len() % 2 == 0 || b.iter()
+            .zip(b.iter().rev())
+            .any(|(&x, &y)| Float::abs(x - y) > tol)
+        {
+            return Err(FTrans2Error::PrototypeNotSymmetric)
+        }
+        let n = b.len()/2;
+
+        let t = transformation.into_option()
+            .unwrap_or_else(|| {
+                let eighth = (two*two*two).recip();
+                Array2::from_shape_vec((3, 3), vec![
+                    eighth, two*eighth, eighth,
+                    two*eighth, -(two + two)*eighth, two*eighth,
+                    eighth, two*eighth, eighth
+                ]).unwrap()
+            });
+        let (tm, tn) = t.dim();
+        if tm % 2 == 0 || tn % 2 == 0
+        {
+            return Err(FTrans2Error::TransformationNotOdd)
+        }
+
+        // Cosine series coefficients of the zero-phase prototype, H(w) = sum a_k cos(kw).
+        let a: Vec<T> = (0..=n).map(|k| if k == 0 {b[n]} else {two*b[n + k]})
+            .collect();
+
+        let conv = |x: &Array2<T>, y: &Array2<T>| {
+            let (xm, xn) = x.dim();
+            let (ym, yn) = y.dim();
+            let mut z = Array2::from_elem((xm + ym - 1, xn + yn - 1), zero);
+            for ((i, j), &x) in x.indexed_iter()
+            {
+                for ((k, l), &y) in y.indexed_iter()
+                {
+                    z[(i + k, j + l)] = z[(i + k, j + l)] + x*y
+                }
+            }
+            z
+        };
+        // Adds y to x, with both centered.
+        let add_centered = |x: &mut Array2<T>, y: &Array2<T>, scale: T| {
+            let (xm, xn) = x.dim();
+            let (ym, yn) = y.dim();
+            let (di, dj) = ((xm - ym)/2, (xn - yn)/2);
+            for ((i, j), &y) in y.indexed_iter()
+            {
+                x[(i + di, j + dj)] = x[(i + di, j + dj)] + y*scale
+            }
+        };
+
+        // Chebyshev recursion, T_k(t) = 2 t T_{k-1}(t) - T_{k-2}(t).
+        let size = (n*(tm - 1) + 1, n*(tn - 1) + 1);
+        let mut h = Array2::from_elem(size, zero);
+        let mut p0 = Array2::from_elem((1, 1), one);
+        let mut p1 = t.clone();
+        add_centered(&mut h, &p0, a[0]);
+        if n >= 1
+        {
+            add_centered(&mut h, &p1, a[1]);
+        }
+        for &a in a.iter()
+            .skip(2)
+        {
+            let mut p2 = conv(&p1, &t);
+            p2.mapv_inplace(|p| p*two);
+            add_centered(&mut p2, &p0, -one);
+            add_centered(&mut h, &p2, a);
+            p0 = p1;
+            p1 = p2;
+        }
+
+        Ok(h)
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::PI;
+
+    use ndarray::Array2;
+
+    use crate::{gen::filter::{FTrans2, Fir1, Fir1Type}, systems::Tf};
+
+    #[test]
+    fn test()
+    {
+        const N: usize = 21;
+        let b: Tf<f64, [_; N]> = Tf::fir1((), [0.5], Fir1Type::LowPass, (), true, ())
+            .unwrap();
+
+        let h = Array2::ftrans2(b, ())
+            .unwrap();
+
+        assert_eq!(h.dim(), (N, N));
+        // The McClellan transformation is symmetric in both axes and across the diagonal.
+        for ((i, j), &x) in h.indexed_iter()
+        {
+            assert!((x - h[(j, i)]).abs() < 1e-12);
+            assert!((x - h[(N - 1 - i, j)]).abs() < 1e-12);
+        }
+
+        // Zero-phase frequency response of the kernel, normalized to pi.
+        let response = |w1: f64, w2: f64| h.indexed_iter()
+            .map(|((i, j), &x)| x*(PI*w1*(i as f64 - (N/2) as f64) + PI*w2*(j as f64 - (N/2) as f64)).cos())
+            .sum::<f64>();
+
+        assert!((response(0.0, 0.0) - 1.0).abs() < 1e-9);
+        assert!((response(0.1, 0.0) - 1.0).abs() < 0.02);
+        assert!((response(0.1, 0.1) - 1.0).abs() < 0.02);
+        assert!(response(0.9, 0.0).abs() < 0.01);
+        assert!(response(0.8, 0.8).abs() < 0.01);
+    }
+}
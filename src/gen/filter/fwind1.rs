@@ -0,0 +1,107 @@
+use ndarray::Array2;
+use num::{Float, NumCast};
+
+use crate::{gen::filter::{FWind2, FWindError}, quantities::{List, MaybeList}};
+
+/// 2-D FIR filter design by the window method, using a 1-D window.
+///
+/// With a single window, a circularly symmetric 2-D window is made by rotating it. With two windows, a separable 2-D window
+/// is made from their outer product. The desired response is sampled as in [FWind2].
+pub trait FWind1<T, W1, W2>: Sized
+where
+    T: Float,
+    W1: List<T>,
+    W2: MaybeList<T>
+{
+    fn fwind1(desired_response: Array2<T>, window: W1, window2: W2) -> Result<Self, FWindError>;
+}
+
+impl<T, W1, W2> FWind1<T, W1, W2> for Array2<T>
+where
+    T: Float,
+    W1: List<T>,
+    W2: MaybeList<T>,
+    Array2<T>: FWind2<T>
+{
+    fn fwind1(hd: Array2<T>, window: W1, window2: W2) -> Result<Self, FWindError>
+    {
+        let zero = T::zero();
+        let one = T::one();
+        let two = one + one;
+
+        let w1 = window.as_view_slice();
+        if w1.is_empty()
+        {
+            return Err(FWindError::EmptyWindow)
+        }
+
+        let w = match window2.as_view_slice_option()
+        {
+            Some(w2) => {
+                if w2.is_empty()
+                {
+                    return Err(FWindError::EmptyWindow)
+                }
+                Array2::from_shape_fn((w1.len(), w2.len()), |(i, j)| w1[i]*w2[j])
+            },
+            None => {
+                let n = w1.len();
+                if n == 1
+                {
+                    Array2::from_elem((1, 1), w1[0])
+                }
+                else
+                {
+                    // Window positions span [-1, 1], and the window is linearly interpolated at each radius.
+                    let nf = <T as NumCast>::from(n - 1).unwrap();
+                    let t = |k: usize| two*<T as NumCast>::from(k).unwrap()/nf - one;
+                    let at = |r: T| {
+                        if r > one
+                        {
+                            return zero
+                        }
+                        let x = (r + one)*nf/two;
+                        let k = <usize as NumCast>::from(x.floor()).unwrap().min(n - 2);
+                        let frac = x - <T as NumCast>::from(k).unwrap();
+                        w1[k] + (w1[k + 1] - w1[k])*frac
+                    };
+                    Array2::from_shape_fn((n, n), |(i, j)| at(t(i).hypot(t(j))))
+                }
+            }
+        };
+
+        Array2::fwind2(hd, w)
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use ndarray::Array2;
+
+    use crate::{gen::{filter::FWind1, window::{WindowGen, WindowRange}}, windows::Hamming};
+
+    #[test]
+    fn test()
+    {
+        const N: usize = 21;
+
+        // Circular band-pass between 0.3 and 0.6 of the Nyquist frequency.
+        let f = |k: usize| (k as f64 - (N/2) as f64)*2.0/N as f64;
+        let hd = Array2::from_shape_fn((N, N), |(i, j)| {
+            let r = f(i).hypot(f(j));
+            if r > 0.3 && r < 0.6 {1.0} else {0.0}
+        });
+        let w: [f64; N] = Hamming.window_gen((), WindowRange::Symmetric);
+
+        let h = Array2::fwind1(hd, w, ())
+            .unwrap();
+
+        // Circularly symmetric kernel, with no gain at zero frequency.
+        for ((i, j), &h_ij) in h.indexed_iter()
+        {
+            assert!((h_ij - h[(j, i)]).abs() < 1e-9);
+        }
+        assert!(h.sum().abs() < 0.2);
+    }
+}
@@ -0,0 +1,82 @@
+use ndarray::Array2;
+use num::{Complex, Float};
+use thiserror::Error;
+
+use crate::{quantities::Matrix, transforms::fourier::Idft2d};
+
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum FWindError
+{
+    #[error("Desired frequency response must have the same dimensions as the window.")]
+    DimensionMismatch,
+    #[error("Window must not be empty.")]
+    EmptyWindow
+}
+
+/// 2-D FIR filter design by the window method, using a 2-D window.
+///
+/// The desired response is sampled on the grid f_k = (k - floor(n/2))*2/n (relative to Nyquist) along each axis, so that
+/// zero frequency is at index floor(n/2). The resulting kernel has the dimensions of the window, and can be applied with
+/// [Conv2d](crate::operations::convolution::Conv2d).
+pub trait FWind2<T>: Sized
+where
+    T: Float
+{
+    fn fwind2(desired_response: Array2<T>, window: Array2<T>) -> Result<Self, FWindError>;
+}
+
+impl<T> FWind2<T> for Array2<T>
+where
+    T: Float,
+    Array2<T>: Idft2d<T> + Matrix<T, Mapped<Complex<T>> = Array2<Complex<T>>>
+{
+    fn fwind2(hd: Array2<T>, w: Array2<T>) -> Result<Self, FWindError>
+    {
+        let (m, n) = w.dim();
+        if m == 0 || n == 0
+        {
+            return Err(FWindError::EmptyWindow)
+        }
+        if hd.dim() != (m, n)
+        {
+            return Err(FWindError::DimensionMismatch)
+        }
+
+        // Moves zero frequency to the origin, and the time origin back to the center.
+        let hd = Array2::from_shape_fn((m, n), |(i, j)| hd[((i + m/2) % m, (j + n/2) % n)]);
+        let h = hd.idft_2d();
+
+        Ok(Array2::from_shape_fn((m, n), |(i, j)| h[((i + (m + 1)/2) % m, (j + (n + 1)/2) % n)].re*w[(i, j)]))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use ndarray::Array2;
+
+    use crate::gen::filter::FWind2;
+
+    #[test]
+    fn test()
+    {
+        const N: usize = 21;
+
+        // Circular low-pass with cutoff at half the Nyquist frequency, with a separable Hamming window.
+        let f = |k: usize| (k as f64 - (N/2) as f64)*2.0/N as f64;
+        let hd = Array2::from_shape_fn((N, N), |(i, j)| if f(i).hypot(f(j)) < 0.5 {1.0} else {0.0});
+        let w1: Vec<f64> = (0..N).map(|k| 0.54 - 0.46*(core::f64::consts::TAU*k as f64/(N - 1) as f64).cos())
+            .collect();
+        let w = Array2::from_shape_fn((N, N), |(i, j)| w1[i]*w1[j]);
+
+        let h = Array2::fwind2(hd, w)
+            .unwrap();
+
+        // The kernel is symmetric about its center.
+        for ((i, j), &h_ij) in h.indexed_iter()
+        {
+            assert!((h_ij - h[(N - 1 - i, N - 1 - j)]).abs() < 1e-9);
+        }
+        assert!((h.sum() - 1.0).abs() < 0.1);
+    }
+}
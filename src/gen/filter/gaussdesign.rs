@@ -0,0 +1,83 @@
+use num::{traits::FloatConst, Float, NumCast};
+
+use crate::{gen::filter::{PulseNormalization, PulseShapeError}, System, systems::Tf};
+
+pub trait GaussDesign: System + Sized
+where
+    Self::Set: Float
+{
+    fn gaussdesign(
+        bandwidth_time_product: Self::Set,
+        span: usize,
+        samples_per_symbol: usize,
+        normalization: PulseNormalization
+    ) -> Result<Self, PulseShapeError>;
+}
+
+impl<T> GaussDesign for Tf<T, Vec<T>, ()>
+where
+    T: Float + FloatConst
+{
+    fn gaussdesign(
+        bt: T,
+        span: usize,
+        sps: usize,
+        normalization: PulseNormalization
+    ) -> Result<Self, PulseShapeError>
+    {
+        let zero = T::zero();
+        let one = T::one();
+        let two = one + one;
+
+        if !(bt > zero) || !bt.is_finite()
+        {
+            return Err(PulseShapeError::BandwidthTimeProductOutOfRange)
+        }
+        if span == 0
+        {
+            return Err(PulseShapeError::ZeroSpan)
+        }
+        if sps == 0
+        {
+            return Err(PulseShapeError::ZeroSamplesPerSymbol)
+        }
+        let order = span*sps;
+
+        let alpha = (two.ln()/two).sqrt()/bt;
+        let spsf = <T as NumCast>::from(sps).unwrap();
+        let half_order = <T as NumCast>::from(order).unwrap()/two;
+
+        let mut b: Vec<T> = (0..=order).map(|i| {
+                let t = (<T as NumCast>::from(i).unwrap() - half_order)/spsf;
+                T::PI().sqrt()/alpha*(-(t*T::PI()/alpha).powi(2)).exp()
+            }).collect();
+
+        normalization.normalize(&mut b);
+
+        Ok(Tf::new(b, ()))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use array_math::ArrayOps;
+
+    use crate::{plot, gen::filter::{GaussDesign, PulseNormalization}, analysis::RealFreqZ, systems::Tf};
+
+    #[test]
+    fn test()
+    {
+        let h = Tf::<f64, Vec<_>, ()>::gaussdesign(0.3, 3, 8, PulseNormalization::Gain)
+            .unwrap();
+        let gain: f64 = h.b.iter()
+            .sum();
+        assert!((gain - 1.0).abs() < 1e-12);
+
+        const N: usize = 1024;
+        let (h_f, w): ([_; N], _) = h.real_freqz(());
+
+        plot::plot_curves("H(e^jw)", "plots/h_z_gaussdesign.png", [&w.zip(h_f.map(|h| h.norm()))])
+            .unwrap();
+    }
+}
@@ -0,0 +1,291 @@
+use num::{traits::FloatConst, Float, NumCast, Zero};
+use option_trait::Maybe;
+use thiserror::Error;
+
+use crate::{gen::{filter::{FirPm, FirPmError, FirPmType}, window::{WindowGen, WindowRange}}, windows::Kaiser, System, systems::Tf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum HalfBandError
+{
+    #[error("Half-band filter order must be of the form 4k + 2.")]
+    InvalidOrder,
+    #[error("Transition width must be positive, and if the filter is digital; less than 1/2 the sampling frequency, or if no sampling frequency is specified, between 0 and 1.")]
+    TransitionWidthOutOfRange,
+    #[error("Sampling frequency must be a positive number.")]
+    InvalidSamplingFrequency,
+    #[error("Not a half-band filter. Every other tap, except the center tap, must be zero.")]
+    NotHalfBand,
+    #[error("Equiripple design failed: {0}")]
+    FirPm(FirPmError)
+}
+
+/// Design method of a half-band filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HalfBandDesign
+{
+    /// Equiripple design by the Parks-McClellan algorithm.
+    Equiripple,
+    /// Kaiser-windowed sinc. The window parameter is chosen by Kaiser's formulas, for the largest attenuation the order allows
+    /// within the given transition width.
+    Kaiser
+}
+
+pub trait HalfBand<O>: System + Sized
+where
+    Self::Set: Float,
+    O: Maybe<usize>
+{
+    fn halfband<FS>(
+        order: O,
+        transition_width: Self::Set,
+        design: HalfBandDesign,
+        sampling_frequency: FS
+    ) -> Result<Self, HalfBandError>
+    where
+        FS: Maybe<Self::Set>;
+}
+
+impl<T> HalfBand<usize> for Tf<T, Vec<T>, ()>
+where
+    T: Float + FloatConst,
+    Self: FirPm + System<Set = T>
+{
+    fn halfband<FS>(
+        order: usize,
+        mut transition_width: T,
+        design: HalfBandDesign,
+        sampling_frequency: FS
+    ) -> Result<Self, HalfBandError>
+    where
+        FS: Maybe<T>
+    {
+        let zero = T::zero();
+        let one = T::one();
+        let two = one + one;
+        let half = two.recip();
+
+        if order % 4 != 2
+        {
+            return Err(HalfBandError::InvalidOrder)
+        }
+        if let Some(fs) = sampling_frequency.into_option()
+        {
+            if !(fs > zero) || !fs.is_finite()
+            {
+                return Err(HalfBandError::InvalidSamplingFrequency)
+            }
+            transition_width = transition_width/(fs*half);
+        }
+        if !(transition_width > zero) || !(transition_width < one)
+        {
+            return Err(HalfBandError::TransitionWidthOutOfRange)
+        }
+
+        let c = order/2;
+        let mut b = match design
+        {
+            HalfBandDesign::Equiripple => {
+                let (h, _, ()) = Self::firpm(
+                    order,
+                    [zero, half - transition_width*half, half + transition_width*half, one],
+                    [one, one, zero, zero],
+                    [one, one],
+                    FirPmType::Symmetric,
+                    (),
+                    T::from(3.0).unwrap(),
+                    T::from(3.0).unwrap(),
+                    T::from(3.0).unwrap(),
+                    T::from(3.0).unwrap()
+                ).map_err(HalfBandError::FirPm)?;
+                h.b.into_inner()
+            },
+            HalfBandDesign::Kaiser => {
+                // Kaiser's order estimate, solved for the attenuation, with the transition width in radians per sample.
+                let attenuation = <T as NumCast>::from(2.285).unwrap()*<T as NumCast>::from(order).unwrap()*T::PI()*transition_width
+                    + <T as NumCast>::from(8.0).unwrap();
+                let beta = if attenuation > <T as NumCast>::from(50.0).unwrap()
+                {
+                    <T as NumCast>::from(0.1102).unwrap()*(attenuation - <T as NumCast>::from(8.7).unwrap())
+                }
+                else if attenuation >= <T as NumCast>::from(21.0).unwrap()
+                {
+                    let am21 = attenuation - <T as NumCast>::from(21.0).unwrap();
+                    <T as NumCast>::from(0.5842).unwrap()*am21.powf(<T as NumCast>::from(0.4).unwrap())
+                        + <T as NumCast>::from(0.07886).unwrap()*am21
+                }
+                else
+                {
+                    zero
+                };
+                let w: Vec<T> = Kaiser {beta}
+                    .window_gen(order + 1, WindowRange::Symmetric);
+                w.into_iter()
+                    .enumerate()
+                    .map(|(k, w)| {
+                        let x = <T as NumCast>::from(k as isize - c as isize).unwrap()*half;
+                        let sinc = if x.is_zero()
+                        {
+                            one
+                        }
+                        else
+                        {
+                            (T::PI()*x).sin()/(T::PI()*x)
+                        };
+                        half*sinc*w
+                    }).collect()
+            }
+        };
+        b.resize(order + 1, zero);
+
+        // Enforce the half-band structure exactly, since the designs above only approximate it numerically.
+        for k in 0..=c
+        {
+            let h = if k == c
+            {
+                half
+            }
+            else if (c - k) % 2 == 0
+            {
+                zero
+            }
+            else
+            {
+                (b[k] + b[order - k])*half
+            };
+            b[k] = h;
+            b[order - k] = h;
+        }
+        let s = b.iter()
+            .enumerate()
+            .filter(|&(k, _)| k != c)
+            .map(|(_, &b)| b)
+            .fold(zero, |a, b| a + b);
+        if !s.is_zero()
+        {
+            let g = half/s;
+            for (k, b) in b.iter_mut()
+                .enumerate()
+            {
+                if k != c
+                {
+                    *b = *b*g
+                }
+            }
+        }
+
+        Ok(Tf::new(b, ()))
+    }
+}
+
+impl<T, const N: usize> HalfBand<()> for Tf<T, [T; N], ()>
+where
+    T: Float + FloatConst,
+    Tf<T, Vec<T>, ()>: HalfBand<usize> + System<Set = T>,
+    [(); N - 1]:
+{
+    fn halfband<FS>(
+        (): (),
+        transition_width: T,
+        design: HalfBandDesign,
+        sampling_frequency: FS
+    ) -> Result<Self, HalfBandError>
+    where
+        FS: Maybe<T>
+    {
+        let h = Tf::halfband(N - 1, transition_width, design, sampling_frequency)?;
+
+        Ok(Tf::new(
+            h.b.into_inner()
+                .try_into()
+                .ok()
+                .unwrap(),
+            ()
+        ))
+    }
+}
+
+pub(crate) fn halfband_taps<T>(b: &[T]) -> Result<(usize, Vec<(usize, T)>, T), HalfBandError>
+where
+    T: Float
+{
+    let n = b.len();
+    if n < 3 || (n - 1) % 4 != 2
+    {
+        return Err(HalfBandError::NotHalfBand)
+    }
+    let c = (n - 1)/2;
+    let tol = b.iter()
+        .map(|b| b.abs())
+        .fold(T::zero(), Float::max)*T::epsilon()*T::from(n).unwrap();
+    if b.iter()
+        .enumerate()
+        .any(|(k, b)| k != c && k % 2 == 1 && b.abs() > tol)
+    {
+        return Err(HalfBandError::NotHalfBand)
+    }
+
+    Ok((
+        c,
+        b.iter()
+            .copied()
+            .enumerate()
+            .step_by(2)
+            .collect(),
+        b[c]
+    ))
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::PI;
+
+    use array_math::ArrayOps;
+    use num::Complex;
+
+    use crate::{plot, gen::filter::{HalfBand, HalfBandDesign}, Plane, analysis::RealFreqZ, systems::{Tf, Zpk}, transforms::system::ToZpk};
+
+    #[test]
+    fn test()
+    {
+        let h: Tf<f64, [_; 31]> = Tf::halfband((), 0.1, HalfBandDesign::Equiripple, ())
+            .unwrap();
+
+        for (k, &b) in h.b.iter()
+            .enumerate()
+        {
+            if k != 15 && k % 2 == 1
+            {
+                assert_eq!(b, 0.0)
+            }
+        }
+        assert_eq!(h.b[15], 0.5);
+
+        const N: usize = 1024;
+        let (h_f, w): ([_; N], _) = h.real_freqz(());
+
+        plot::plot_curves("H(e^jw)", "plots/h_z_halfband.png", [&w.zip(h_f.map(|h| h.norm()))])
+            .unwrap();
+
+        let h: Zpk<_, Vec<_>, Vec<_>, _> = h.to_zpk((), ());
+
+        plot::plot_pz("H(z)", "plots/pz_z_halfband.png", &h.p, &h.z, Plane::Z)
+            .unwrap();
+
+        // The Kaiser design trades attenuation for transition width. Kaiser's estimates for this order are 51 dB and 30 dB.
+        for (width, attenuation) in [(0.2, 45.0), (0.1, 25.0)]
+        {
+            let h: Tf<f64, Vec<_>> = Tf::halfband(30, width, HalfBandDesign::Kaiser, ())
+                .unwrap();
+            let stopband = (0..=100).map(|i| {
+                    let f = 0.5 + width/2.0 + (0.5 - width/2.0)*i as f64/100.0;
+                    h.b.iter()
+                        .enumerate()
+                        .map(|(k, &b)| b*Complex::cis(-PI*f*k as f64))
+                        .sum::<Complex<f64>>()
+                        .norm()
+                }).fold(0.0, f64::max);
+            assert!(20.0*stopband.log10() < -attenuation, "{} dB", 20.0*stopband.log10());
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use core::{iter::Sum, ops::{AddAssign, MulAssign}};
+
+use ndarray::{Array1, Array2};
+use ndarray_linalg::{least_squares::LeastSquaresSvd, Lapack};
+use num::{traits::FloatConst, Complex, Float, NumCast, Zero};
+use option_trait::Maybe;
+use thiserror::Error;
+
+use crate::{gen::filter::{magnitude_grid, magnitude_points, minimum_phase_spectrum, YuleWalkError}, quantities::{List, MaybeList}, Plane, System, systems::Tf, transforms::filter::Stabilize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum IirLsError
+{
+    #[error("Denominator order must be at least 1.")]
+    ZeroOrder,
+    #[error("List of frequencies and list of magnitudes must have equal length.")]
+    FrequenciesAndMagnitudesDifferentLength,
+    #[error("List of frequencies and list of weights must have equal length.")]
+    FrequenciesAndWeightsDifferentLength,
+    #[error("Frequencies must be monotonic starting at zero.")]
+    FrequenciesNotNondecreasing,
+    #[error("Frequencies must start at zero and end at 1/2 the sampling frequency, or if no sampling frequency is specified, end at 1.")]
+    FrequenciesOutOfRange,
+    #[error("Magnitudes must be non-negative.")]
+    NegativeMagnitude,
+    #[error("Weights must be non-negative, and not all zero.")]
+    WeightsOutOfRange,
+    #[error("Sampling frequency must be a positive number.")]
+    InvalidSamplingFrequency,
+    #[error("Numerical error.")]
+    NumericalError
+}
+
+impl From<YuleWalkError> for IirLsError
+{
+    fn from(error: YuleWalkError) -> Self
+    {
+        match error
+        {
+            YuleWalkError::ZeroOrder => IirLsError::ZeroOrder,
+            YuleWalkError::FrequenciesAndMagnitudesDifferentLength => IirLsError::FrequenciesAndMagnitudesDifferentLength,
+            YuleWalkError::FrequenciesNotNondecreasing => IirLsError::FrequenciesNotNondecreasing,
+            YuleWalkError::FrequenciesOutOfRange => IirLsError::FrequenciesOutOfRange,
+            YuleWalkError::NegativeMagnitude => IirLsError::NegativeMagnitude,
+            YuleWalkError::InvalidSamplingFrequency => IirLsError::InvalidSamplingFrequency,
+            YuleWalkError::NumericalError => IirLsError::NumericalError
+        }
+    }
+}
+
+/// Weighted least-squares fit of a stable recursive filter to a piecewise-linear magnitude response.
+///
+/// Returns the filter and its weighted RMS magnitude error.
+pub trait IirLs<NB, NA, F, M, W>: System + Sized
+where
+    Self::Set: Float,
+    NB: Maybe<usize>,
+    NA: Maybe<usize>,
+    F: List<Self::Set>,
+    M: List<Self::Set, Length = F::Length>,
+    W: MaybeList<Self::Set>
+{
+    fn iirls<FS>(
+        numerator_order: NB,
+        denominator_order: NA,
+        frequencies: F,
+        magnitudes: M,
+        weights: W,
+        sampling_frequency: FS
+    ) -> Result<(Self, Self::Set), IirLsError>
+    where
+        FS: Maybe<Self::Set>;
+}
+
+impl<T, F, M, W> IirLs<usize, usize, F, M, W> for Tf<T, Vec<T>, Vec<T>>
+where
+    T: Float + FloatConst + Lapack<Real = T, Complex = Complex<T>>,
+    Complex<T>: MulAssign + AddAssign + MulAssign<T> + Sum,
+    F: List<T>,
+    M: List<T, Length = F::Length>,
+    W: MaybeList<T>,
+    Self: Stabilize<Output = Self> + System<Set = T>
+{
+    fn iirls<FS>(
+        nb: usize,
+        na: usize,
+        frequencies: F,
+        magnitudes: M,
+        weights: W,
+        sampling_frequency: FS
+    ) -> Result<(Self, T), IirLsError>
+    where
+        FS: Maybe<T>
+    {
+        let zero = T::zero();
+        let one = T::one();
+
+        if na < 1
+        {
+            return Err(IirLsError::ZeroOrder)
+        }
+        let (f, m) = magnitude_points(frequencies.as_view_slice(), magnitudes.as_view_slice(), sampling_frequency)?;
+        let w = match weights.as_view_slice_option()
+        {
+            Some(w) => {
+                if w.len() != f.len()
+                {
+                    return Err(IirLsError::FrequenciesAndWeightsDifferentLength)
+                }
+                if w.iter().any(|&w| !(w >= zero) || !w.is_finite()) || w.iter().all(|w| w.is_zero())
+                {
+                    return Err(IirLsError::WeightsOutOfRange)
+                }
+                w.to_vec()
+            },
+            None => vec![one; f.len()]
+        };
+
+        let npt = 512.max((8*(nb + na)).next_power_of_two());
+        let nfft = 2*npt;
+        let mag = magnitude_grid(&f, &m, npt + 1);
+        let wt = magnitude_grid(&f, &w, npt + 1);
+        let z: Vec<Complex<T>> = (0..=npt).map(|k| Complex::cis(-T::PI()*<T as NumCast>::from(k).unwrap()/<T as NumCast>::from(npt).unwrap()))
+            .collect();
+        let polyval = |c: &[T], z: Complex<T>| c.iter()
+            .rev()
+            .fold(Complex::zero(), |y: Complex<T>, &c| y*z + c);
+
+        // Start out with the minimum-phase response having the desired magnitude.
+        let floor = mag.iter()
+            .copied()
+            .fold(zero, Float::max)*<T as NumCast>::from(1e-5).unwrap();
+        let log_mag: Vec<T> = (0..nfft).map(|k| Float::ln(Float::max(mag[if k <= npt {k} else {nfft - k}], floor)))
+            .collect();
+        let mut d: Vec<Complex<T>> = minimum_phase_spectrum(&log_mag);
+        d.truncate(npt + 1);
+
+        let numerator = |a: &[T], d: &[Complex<T>]| -> Result<Vec<T>, IirLsError> {
+            let (rows, rhs): (Vec<_>, Vec<_>) = (0..=npt).map(|k| {
+                    let s = Float::sqrt(wt[k]);
+                    let y = d[k]*polyval(a, z[k])*s;
+                    ((k, s), y)
+                }).unzip();
+            let phi = Array2::from_shape_fn((2*(npt + 1), nb + 1), |(i, j)| {
+                let (k, s) = rows[i % (npt + 1)];
+                let p = z[k].powi(j as i32)*s;
+                if i <= npt {p.re} else {p.im}
+            });
+            let y = Array1::from_shape_fn(2*(npt + 1), |i| if i <= npt {rhs[i].re} else {rhs[i - npt - 1].im});
+            phi.least_squares(&y)
+                .map(|b| b.solution.to_vec())
+                .map_err(|_| IirLsError::NumericalError)
+        };
+
+        let sum_w = wt.iter()
+            .copied()
+            .fold(zero, |a, b| a + b);
+        let mut a = vec![one];
+        a.resize(na + 1, zero);
+        let mut best: Option<(Vec<T>, Vec<T>, T)> = None;
+        for _ in 0..32
+        {
+            // Steiglitz-McBride: linearized equation error, prefiltered by the previous denominator.
+            let ap: Vec<Complex<T>> = z.iter()
+                .map(|&z| polyval(&a, z))
+                .collect();
+            let n = nb + 1 + na;
+            let phi = Array2::from_shape_fn((2*(npt + 1), n), |(i, j)| {
+                let k = i % (npt + 1);
+                let s = Float::sqrt(wt[k])/ap[k].norm();
+                let p = if j <= nb
+                {
+                    z[k].powi(j as i32)
+                }
+                else
+                {
+                    -d[k]*z[k].powi((j - nb) as i32)
+                }*s;
+                if i <= npt {p.re} else {p.im}
+            });
+            let y = Array1::from_shape_fn(2*(npt + 1), |i| {
+                let k = i % (npt + 1);
+                let y = d[k]*Float::sqrt(wt[k])/ap[k].norm();
+                if i <= npt {y.re} else {y.im}
+            });
+            let x = phi.least_squares(&y)
+                .map_err(|_| IirLsError::NumericalError)?
+                .solution;
+            let a_next: Vec<T> = core::iter::once(one)
+                .chain(x.iter().skip(nb + 1).copied())
+                .collect();
+            if a_next.iter().any(|a| !a.is_finite())
+            {
+                break
+            }
+            a = Tf::new(vec![one], a_next)
+                .stabilize(Plane::Z)
+                .a
+                .into_inner();
+
+            // The numerator is refitted against the stabilized denominator, so every iterate is stable.
+            let b = numerator(&a, &d)?;
+
+            let h: Vec<Complex<T>> = z.iter()
+                .map(|&z| polyval(&b, z)/polyval(&a, z))
+                .collect();
+            let e = Float::sqrt(h.iter()
+                .zip(mag.iter().zip(wt.iter()))
+                .map(|(h, (&m, &w))| {
+                    let e = h.norm() - m;
+                    e*e*w
+                }).fold(zero, |a, b| a + b)/sum_w);
+            let converged = best.as_ref()
+                .is_some_and(|(_, _, e_best)| Float::abs(*e_best - e) <= e*<T as NumCast>::from(1e-6).unwrap());
+            if best.as_ref().map_or(true, |(_, _, e_best)| e < *e_best)
+            {
+                best = Some((b, a.clone(), e))
+            }
+            if converged
+            {
+                break
+            }
+
+            // Only the magnitude is prescribed, so the phase follows the current fit.
+            for ((d, h), &m) in d.iter_mut()
+                .zip(h.iter())
+                .zip(mag.iter())
+            {
+                if h.norm() > zero
+                {
+                    *d = *h/h.norm()*m
+                }
+            }
+        }
+
+        let (b, a, e) = best.ok_or(IirLsError::NumericalError)?;
+
+        Ok((Tf::new(b, a), e))
+    }
+}
+
+impl<T, F, M, W, const NB: usize, const NA: usize> IirLs<(), (), F, M, W> for Tf<T, [T; NB], [T; NA]>
+where
+    T: Float,
+    F: List<T>,
+    M: List<T, Length = F::Length>,
+    W: MaybeList<T>,
+    Tf<T, Vec<T>, Vec<T>>: IirLs<usize, usize, F, M, W> + System<Set = T>,
+    [(); NB - 1]:,
+    [(); NA - 2]:
+{
+    fn iirls<FS>(
+        (): (),
+        (): (),
+        frequencies: F,
+        magnitudes: M,
+        weights: W,
+        sampling_frequency: FS
+    ) -> Result<(Self, T), IirLsError>
+    where
+        FS: Maybe<T>
+    {
+        let (h, e) = Tf::iirls(NB - 1, NA - 1, frequencies, magnitudes, weights, sampling_frequency)?;
+
+        Ok((
+            Tf::new(
+                h.b.into_inner()
+                    .try_into()
+                    .ok()
+                    .unwrap(),
+                h.a.into_inner()
+                    .try_into()
+                    .ok()
+                    .unwrap()
+            ),
+            e
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::PI;
+
+    use array_math::ArrayOps;
+
+    use crate::{plot, gen::filter::IirLs, Plane, analysis::{IsStable, RealFreqZ}, systems::Tf};
+
+    #[test]
+    fn test()
+    {
+        let f = [0.0, 0.2, 0.3, 0.5, 0.6, 1.0];
+        let m = [1.0, 1.0, 2.0, 2.0, 0.5, 0.5];
+        let (h, e): (Tf<f64, [_; 7], [_; 7]>, _) = Tf::iirls(
+            (),
+            (),
+            f,
+            m,
+            [1.0, 1.0, 1.0, 1.0, 1.0, 1.0],
+            ()
+        ).unwrap();
+
+        assert!(h.is_stable((), Plane::Z));
+        assert!(e < 0.05);
+
+        const N: usize = 1024;
+        let (h_f, w): ([_; N], _) = h.real_freqz(());
+
+        // The magnitude follows the piecewise-linear target within every segment.
+        let target = |x: f64| f.windows(2)
+            .zip(m.windows(2))
+            .find(|(f, _)| x >= f[0] && x <= f[1])
+            .map(|(f, m)| m[0] + (m[1] - m[0])*(x - f[0])/(f[1] - f[0]))
+            .unwrap();
+        for x in [0.1, 0.25, 0.4, 0.55, 0.8]
+        {
+            let i = (x*N as f64).round() as usize;
+            assert!((h_f[i].norm() - target(w[i]/PI)).abs() < 0.05);
+        }
+
+        plot::plot_curves("H(e^jw)", "plots/h_z_iirls.png", [&w.zip(h_f.map(|h| h.norm()))])
+            .unwrap();
+    }
+}
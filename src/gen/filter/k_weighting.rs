@@ -0,0 +1,101 @@
+use num::{traits::FloatConst, Float, NumCast};
+
+use crate::{gen::filter::FilterGenError, System, systems::{Sos, Tf}};
+
+/// Parameters of the analog prototypes of the two K-weighting stages, fitted so that at 48 kHz the bilinear designs
+/// reproduce the coefficients tabulated in ITU-R BS.1770-4.
+const SHELF_FREQUENCY: f64 = 1681.974450955533;
+const SHELF_GAIN_DB: f64 = 3.999843853973347;
+const SHELF_Q: f64 = 0.7071752369554196;
+const SHELF_BAND_EXP: f64 = 0.4996667741545416;
+const HIGHPASS_FREQUENCY: f64 = 38.13547087602444;
+const HIGHPASS_Q: f64 = 0.5003270373238773;
+
+pub trait KWeighting: System + Sized
+{
+    /// K-weighting filter of ITU-R BS.1770, i.e. the high-shelf head model followed by the RLB high-pass, at any sampling frequency.
+    fn k_weighting(sampling_frequency: <Self as System>::Set) -> Result<Self, FilterGenError>;
+}
+
+impl<T> KWeighting for Sos<T, [T; 3], [T; 3], [Tf<T, [T; 3], [T; 3]>; 2]>
+where
+    T: Float + FloatConst,
+    Self: System<Set = T>
+{
+    fn k_weighting(sampling_frequency: T) -> Result<Self, FilterGenError>
+    {
+        let one = T::one();
+        let two = one + one;
+        let c = |x: f64| <T as NumCast>::from(x).unwrap();
+
+        let fs = sampling_frequency;
+        if !(fs > two*c(SHELF_FREQUENCY)) || !fs.is_finite()
+        {
+            return Err(FilterGenError::FrequenciesOutOfRange)
+        }
+
+        let shelf = {
+            let k = (T::PI()*c(SHELF_FREQUENCY)/fs).tan();
+            let q = c(SHELF_Q);
+            let vh = c(10.0).powf(c(SHELF_GAIN_DB)/c(20.0));
+            let vb = vh.powf(c(SHELF_BAND_EXP));
+            let a0 = one + k/q + k*k;
+            Tf::new(
+                [(vh + vb*k/q + k*k)/a0, two*(k*k - vh)/a0, (vh - vb*k/q + k*k)/a0],
+                [one, two*(k*k - one)/a0, (one - k/q + k*k)/a0]
+            )
+        };
+        let highpass = {
+            let k = (T::PI()*c(HIGHPASS_FREQUENCY)/fs).tan();
+            let q = c(HIGHPASS_Q);
+            let a0 = one + k/q + k*k;
+            Tf::new(
+                [one, -two, one],
+                [one, two*(k*k - one)/a0, (one - k/q + k*k)/a0]
+            )
+        };
+
+        Ok(Sos::new([shelf, highpass]))
+    }
+}
+
+impl<T> KWeighting for Sos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>>
+where
+    T: Float + FloatConst,
+    Sos<T, [T; 3], [T; 3], [Tf<T, [T; 3], [T; 3]>; 2]>: KWeighting + System<Set = T>,
+    Self: System<Set = T>
+{
+    fn k_weighting(sampling_frequency: T) -> Result<Self, FilterGenError>
+    {
+        let h: Sos<T, [T; 3], [T; 3], [Tf<T, [T; 3], [T; 3]>; 2]> = Sos::k_weighting(sampling_frequency)?;
+
+        Ok(Sos::new(h.sos.into_inner().into_iter().collect()))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use crate::{gen::filter::KWeighting, systems::Sos};
+
+    #[test]
+    fn test()
+    {
+        let h: Sos<f64, [_; 3], [_; 3], [_; 2]> = Sos::k_weighting(48000.0)
+            .unwrap();
+
+        // Coefficients tabulated in ITU-R BS.1770-4, table 1 and 2.
+        let [shelf, highpass] = h.sos.into_inner();
+        for (x, y) in shelf.b.into_inner().into_iter()
+            .chain(shelf.a.into_inner())
+            .zip([1.53512485958697, -2.69169618940638, 1.19839281085285, 1.0, -1.69065929318241, 0.73248077421585])
+        {
+            assert!((x - y).abs() < 1e-8)
+        }
+        for (x, y) in highpass.a.into_inner().into_iter()
+            .zip([1.0, -1.99004745483398, 0.99007225036621])
+        {
+            assert!((x - y).abs() < 1e-8)
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use core::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use ndarray::{Array1, Array2};
+use ndarray_linalg::{Lapack, Solve};
+use num::{complex::ComplexFloat, traits::FloatConst, Complex, Float, NumCast};
+use option_trait::Maybe;
+
+use array_math::SliceMath;
+
+use crate::{quantities::ProductSequence, System, systems::Zpk};
+
+pub trait LegendreAP<O>: System + Sized
+where
+    Self::Set: Float,
+    O: Maybe<usize>
+{
+    #[doc(alias = "optimum_l")]
+    fn legendreap(order: O) -> Self;
+}
+
+impl<T> LegendreAP<usize> for Zpk<Complex<T>, (), Vec<Complex<T>>, T>
+where
+    T: Float + FloatConst + AddAssign + MulAssign + Into<Complex<T>> + ComplexFloat<Real = T> + Lapack<Real = T, Complex = Complex<T>>,
+    Complex<T>: From<T> + AddAssign + SubAssign + MulAssign + DivAssign + DivAssign<T>,
+{
+    fn legendreap(order: usize) -> Self
+    {
+        if order == 0
+        {
+            return Self::one()
+        }
+
+        let zero = T::zero();
+        let one = T::one();
+        let two = one + one;
+
+        // Polynomials are stored in ascending powers here.
+        let mul = |a: &[T], b: &[T]| {
+            let mut c = vec![zero; a.len() + b.len() - 1];
+            for (i, &a) in a.iter()
+                .enumerate()
+            {
+                for (j, &b) in b.iter()
+                    .enumerate()
+                {
+                    c[i + j] = c[i + j] + a*b
+                }
+            }
+            c
+        };
+        let integrate = |a: &[T]| core::iter::once(zero)
+            .chain(a.iter()
+                .enumerate()
+                .map(|(i, &a)| a/<T as NumCast>::from(i + 1).unwrap())
+            ).collect::<Vec<_>>();
+        let eval = |a: &[T], x: T| a.iter()
+            .rev()
+            .fold(zero, |y, &a| y*x + a);
+
+        // The slope of L(w^2) must be non-negative. For even orders it is given a zero at w = 0.
+        let even = order % 2 == 0;
+        let k = (order - 1)/2;
+        let weight = if even {vec![one/(two + two), one/(two + two)]} else {vec![one/two]};
+
+        let mut legendre = vec![vec![one], vec![zero, one]];
+        for i in 2..=k
+        {
+            let i_f = <T as NumCast>::from(i).unwrap();
+            let p1 = mul(&legendre[i - 1], &[zero, two*i_f - one]);
+            let p0 = &legendre[i - 2];
+            let p = p1.iter()
+                .enumerate()
+                .map(|(j, &p1)| (p1 - p0.get(j).copied().unwrap_or(zero)*(i_f - one))/i_f)
+                .collect();
+            legendre.push(p)
+        }
+        legendre.truncate(k + 1);
+
+        // Maximize the slope at the cutoff, i.e. v(1)^2, subject to L(1) = 1.
+        let gram = Array2::from_shape_fn((k + 1, k + 1), |(i, j)| {
+            let g = integrate(&mul(&mul(&legendre[i], &legendre[j]), &weight));
+            eval(&g, one) - eval(&g, -one)
+        });
+        let mut a = gram.solve(&Array1::from_elem(k + 1, one))
+            .unwrap();
+        let norm = Float::sqrt(a.dot(&gram.dot(&a)));
+        a.mapv_inplace(|a| a/norm);
+
+        let v = legendre.iter()
+            .zip(a.iter())
+            .fold(vec![zero; k + 1], |v, (p, &a)| v.iter()
+                .zip(p.iter().chain(core::iter::repeat(&zero)))
+                .map(|(&v, &p)| v + p*a)
+                .collect()
+            );
+
+        // Substitute x = 2y - 1, where y = w^2.
+        let mut vy = vec![zero];
+        let mut xi = vec![one];
+        for &v in v.iter()
+        {
+            vy = vy.into_iter()
+                .chain(core::iter::repeat(zero))
+                .zip(xi.iter().copied())
+                .map(|(vy, xi)| vy + xi*v)
+                .collect();
+            xi = mul(&xi, &[-one, two]);
+        }
+        let mut dl = mul(&vy, &vy);
+        if even
+        {
+            dl = mul(&dl, &[zero, one]);
+        }
+        let l = integrate(&dl);
+
+        // |H(s)|^2 = 1/(1 + L(-s^2)), in descending powers of s.
+        let mut q = vec![zero; 2*order + 1];
+        for (m, &l) in l.iter()
+            .enumerate()
+        {
+            q[2*m] = if m % 2 == 0 {l} else {-l}
+        }
+        q[0] = q[0] + one;
+        q.reverse();
+
+        let r: Vec<Complex<T>> = q.rpolynomial_roots();
+        let mut p: Vec<Complex<T>> = r.into_iter()
+            .filter(|p| p.re < zero)
+            .collect();
+        p.truncate(order);
+
+        let k = p.iter()
+            .map(|&p| -p)
+            .product::<Complex<T>>()
+            .re;
+
+        Zpk {
+            z: ProductSequence::new(()),
+            p: ProductSequence::new(p),
+            k
+        }
+    }
+}
+impl<T, const N: usize> LegendreAP<()> for Zpk<Complex<T>, (), [Complex<T>; N], T>
+where
+    T: Float + FloatConst,
+    Zpk<Complex<T>, (), Vec<Complex<T>>, T>: LegendreAP<usize> + System<Set = T>
+{
+    fn legendreap((): ()) -> Self
+    {
+        let Zpk { z, p, k } = Zpk::legendreap(N);
+
+        Zpk {
+            z,
+            p: p.try_into().map_err(|_| ()).unwrap(),
+            k
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use array_math::ArrayOps;
+    use linspace::LinspaceArray;
+    use num::Complex;
+
+    use crate::{plot, gen::filter::LegendreAP, transforms::domain::Bilinear, analysis::{FreqS, RealFreqZ}, systems::Zpk, Plane};
+
+    #[test]
+    fn test()
+    {
+        let fs = 2.0;
+        let h = Zpk::legendreap(6);
+
+        let [h_1] = h.freqs([Complex::new(0.0, 1.0)]);
+        assert!((h_1.norm() - 0.5f64.sqrt()).abs() < 1e-6);
+
+        plot::plot_pz("H(s)", "plots/pz_s_legendreap.png", h.poles(), h.zeros(), Plane::S)
+            .unwrap();
+
+        const N: usize = 1024;
+        let w: [_; N] = (0.0..fs).linspace_array();
+        let h_f = h.freqs(w.map(|w| Complex::new(0.0, w)));
+
+        plot::plot_curves("H(jw)", "plots/h_s_legendreap.png", [&w.zip(h_f.map(|h| h.norm())), &w.zip(h_f.map(|h| h.arg()))])
+            .unwrap();
+
+        let h = h.bilinear(fs)
+            .unwrap();
+
+        let (h_f, w): ([_; N], _) = h.real_freqz(());
+
+        plot::plot_curves("H(e^jw)", "plots/h_z_legendreap.png", [&w.zip(h_f.map(|h| h.norm())), &w.zip(h_f.map(|h| h.arg()))])
+            .unwrap();
+    }
+}
@@ -0,0 +1,29 @@
+use core::ops::{DivAssign, MulAssign};
+
+use num::{traits::FloatConst, Complex, Float};
+
+use crate::{gen::filter::{prototype_ord, LegendreAP, FilterBandError, FilterGenPlane, FilterGenType}, systems::Zpk};
+
+pub fn legendreord<T, const F: usize>(
+    passband_frequencies: [T; F],
+    stopband_frequencies: [T; F],
+    passband_ripple: T,
+    stopband_attenuation: T,
+    plane: FilterGenPlane<T>
+) -> Result<(usize, [T; F], FilterGenType), FilterBandError>
+where
+    T: Float + FloatConst + MulAssign + DivAssign,
+    Zpk<Complex<T>, (), Vec<Complex<T>>, T>: LegendreAP<usize>,
+    [(); F - 1]:,
+    [(); 2 - F]:
+{
+    prototype_ord(
+        passband_frequencies,
+        stopband_frequencies,
+        passband_ripple,
+        stopband_attenuation,
+        plane,
+        |n| Zpk::<Complex<T>, (), Vec<Complex<T>>, T>::legendreap(n).p
+            .to_vec()
+    )
+}
@@ -5,9 +5,11 @@ moddef::moddef!(
     flat(pub) mod {
         besselap,
         besself,
+        besselord,
         buttap,
         butter,
         buttord,
+        cfirpm,
         cheb1ap,
         cheb1ord,
         cheb2ap,
@@ -19,20 +21,45 @@ moddef::moddef!(
         ellipord,
         fir1,
         fir2,
+        fircls,
+        fircls1,
         firgr,
         firls,
         firpm,
         firpmord,
+        ftrans2,
+        fwind1,
+        fwind2,
         gammatone_fir,
         gammatone_iir,
+        gaussdesign,
+        halfband,
         iir_comb,
         iir_design,
         iir_notch,
         iir_peak,
+        iirls,
+        k_weighting,
         kaiserord,
+        legendre,
+        legendreap,
+        legendreord,
+        octave_filter_bank,
         pei_tseng_notch,
+        prototype_ord,
         qp_kaiser,
-        sgolay
+        rcosdesign,
+        sgolay,
+        synctuned,
+        synctunedap,
+        synctunedord,
+        transitional,
+        transitionalap,
+        transitionalord,
+        weighting_filter,
+        wiener,
+        wiener_psd,
+        yulewalk
     }
 );
 
@@ -88,4 +115,6 @@ pub enum FilterBandError
     InvalidSamplingFrequency,
     #[error("One band must surround the other.")]
     BandNotSurrounding,
+    #[error("No filter order up to the supported maximum meets the specification.")]
+    OrderTooHigh,
 }
\ No newline at end of file
@@ -0,0 +1,322 @@
+use core::ops::{AddAssign, MulAssign};
+
+use array_math::SliceMath;
+use num::{traits::FloatConst, Complex, Float, NumCast};
+use thiserror::Error;
+
+use crate::{gen::filter::{Butter, FilterGenError, FilterGenPlane, FilterGenType}, systems::{Sos, Tf}};
+
+/// Base-ten octave ratio, G = 10^(3/10).
+const OCTAVE_RATIO_EXP: f64 = 0.3;
+const REFERENCE_FREQUENCY: f64 = 1000.0;
+
+/// Breakpoints of the relative attenuation acceptance limits of IEC 61260-1:2014 (table 1) for octave-band filters.
+///
+/// Each row is the exponent e of the normalized frequency G^e, followed by the class 1 and class 2 lower and upper limits in dB.
+const MASK: [(f64, [f64; 4]); 9] = [
+    (0.0, [-0.4, 0.4, -0.6, 0.6]),
+    (0.125, [-0.4, 0.5, -0.6, 0.7]),
+    (0.25, [-0.4, 0.7, -0.6, 0.9]),
+    (0.375, [-0.4, 1.4, -0.6, 1.7]),
+    (0.5, [1.2, 5.3, 0.8, 5.5]),
+    (1.0, [16.6, f64::INFINITY, 15.6, f64::INFINITY]),
+    (2.0, [40.5, f64::INFINITY, 39.5, f64::INFINITY]),
+    (3.0, [60.0, f64::INFINITY, 54.0, f64::INFINITY]),
+    (4.0, [70.0, f64::INFINITY, 60.0, f64::INFINITY])
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum OctaveFilterBankError
+{
+    #[error("Filter order must be at least 1.")]
+    ZeroOrder,
+    #[error("Sampling frequency must be a positive number.")]
+    InvalidSamplingFrequency,
+    #[error("Frequency range must be positive, increasing, and every band must lie below 1/2 the sampling frequency.")]
+    FrequencyRangeOutOfRange,
+    #[error("No bands within the frequency range.")]
+    NoBands,
+    #[error("Band filter design failed: {0}")]
+    FilterGen(FilterGenError)
+}
+
+/// Bandwidth designator, i.e. the fraction of an octave spanned by each band.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OctaveFraction
+{
+    Full,
+    Third,
+    Sixth,
+    Twelfth,
+    TwentyFourth
+}
+
+impl OctaveFraction
+{
+    pub fn bands_per_octave(self) -> usize
+    {
+        match self
+        {
+            OctaveFraction::Full => 1,
+            OctaveFraction::Third => 3,
+            OctaveFraction::Sixth => 6,
+            OctaveFraction::Twelfth => 12,
+            OctaveFraction::TwentyFourth => 24
+        }
+    }
+}
+
+/// Performance class of IEC 61260-1 / ANSI S1.11.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OctaveFilterClass
+{
+    Class1,
+    Class2
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OctaveBand<T>
+where
+    T: Float
+{
+    /// Band number x, where band zero is centered at the 1 kHz reference frequency.
+    pub index: isize,
+    /// Exact midband frequency.
+    pub center_frequency: T,
+    pub lower_frequency: T,
+    pub upper_frequency: T
+}
+
+#[derive(Debug, Clone)]
+pub struct OctaveFilterBank<T>
+where
+    T: Float
+{
+    pub fraction: OctaveFraction,
+    pub sampling_frequency: T,
+    pub bands: Vec<OctaveBand<T>>,
+    pub filters: Vec<Sos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>>>
+}
+
+impl<T> OctaveFilterBank<T>
+where
+    T: Float + FloatConst + AddAssign + MulAssign,
+    Complex<T>: AddAssign + MulAssign,
+    Sos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>>: Butter<usize>
+{
+    /// Designs a bank of Butterworth band-pass filters, one per band with its midband frequency in `frequency_range`.
+    ///
+    /// Midband frequencies are base-ten, as specified by IEC 61260-1. Each band filter has order `2*order`.
+    pub fn new(
+        fraction: OctaveFraction,
+        frequency_range: [T; 2],
+        order: usize,
+        sampling_frequency: T
+    ) -> Result<Self, OctaveFilterBankError>
+    {
+        let zero = T::zero();
+        let one = T::one();
+        let two = one + one;
+
+        if order < 1
+        {
+            return Err(OctaveFilterBankError::ZeroOrder)
+        }
+        if !(sampling_frequency > zero) || !sampling_frequency.is_finite()
+        {
+            return Err(OctaveFilterBankError::InvalidSamplingFrequency)
+        }
+        if !(frequency_range[0] > zero) || !(frequency_range[1] >= frequency_range[0])
+        {
+            return Err(OctaveFilterBankError::FrequencyRangeOutOfRange)
+        }
+
+        let b = fraction.bands_per_octave();
+        let bf = <T as NumCast>::from(b).unwrap();
+        let g = Self::octave_ratio();
+        let fr = <T as NumCast>::from(REFERENCE_FREQUENCY).unwrap();
+
+        // Inverse of the midband frequency formula, for odd and even b respectively.
+        let x = |f: T| {
+            let e = bf*(f/fr).log(g);
+            if b % 2 == 1 {e} else {e - two.recip()}
+        };
+        let x_min = <isize as NumCast>::from(x(frequency_range[0]).ceil()).unwrap();
+        let x_max = <isize as NumCast>::from(x(frequency_range[1]).floor()).unwrap();
+        if x_max < x_min
+        {
+            return Err(OctaveFilterBankError::NoBands)
+        }
+
+        let bands: Vec<_> = (x_min..=x_max).map(|index| Self::band(fraction, index))
+            .collect();
+        if bands.last().is_some_and(|band| !(band.upper_frequency < sampling_frequency/two))
+        {
+            return Err(OctaveFilterBankError::FrequencyRangeOutOfRange)
+        }
+
+        let filters = bands.iter()
+            .map(|band| Sos::butter(
+                order,
+                [band.lower_frequency, band.upper_frequency],
+                FilterGenType::BandPass,
+                FilterGenPlane::Z { sampling_frequency: Some(sampling_frequency) }
+            )).collect::<Result<Vec<_>, _>>()
+            .map_err(OctaveFilterBankError::FilterGen)?;
+
+        Ok(Self {
+            fraction,
+            sampling_frequency,
+            bands,
+            filters
+        })
+    }
+
+    fn octave_ratio() -> T
+    {
+        <T as NumCast>::from(10.0f64.powf(OCTAVE_RATIO_EXP)).unwrap()
+    }
+
+    /// Exact midband and band-edge frequencies of band number `index`.
+    pub fn band(fraction: OctaveFraction, index: isize) -> OctaveBand<T>
+    {
+        let two = T::one() + T::one();
+        let b = fraction.bands_per_octave();
+        let bf = <T as NumCast>::from(b).unwrap();
+        let g = Self::octave_ratio();
+        let fr = <T as NumCast>::from(REFERENCE_FREQUENCY).unwrap();
+        let x = <T as NumCast>::from(index).unwrap();
+
+        let center_frequency = if b % 2 == 1
+        {
+            fr*g.powf(x/bf)
+        }
+        else
+        {
+            fr*g.powf((two*x + T::one())/(two*bf))
+        };
+        let edge = g.powf((two*bf).recip());
+
+        OctaveBand {
+            index,
+            center_frequency,
+            lower_frequency: center_frequency/edge,
+            upper_frequency: center_frequency*edge
+        }
+    }
+
+    /// Magnitude response of each band filter at the frequency `f` (in the same unit as the sampling frequency).
+    pub fn magnitude(&self, f: T) -> Vec<T>
+    {
+        let z = Complex::cis(T::TAU()*f/self.sampling_frequency);
+        self.filters.iter()
+            .map(|sos| sos.sos.iter()
+                .map(|tf| {
+                    let b: Vec<Complex<T>> = tf.b.iter().map(|&b| b.into()).collect();
+                    let a: Vec<Complex<T>> = tf.a.iter().map(|&a| a.into()).collect();
+                    (b.rpolynomial(z)/a.rpolynomial(z)).norm()
+                }).fold(T::one(), |h, hk| h*hk)
+            ).collect()
+    }
+
+    /// Smallest margin in dB of each band filter against the acceptance limits of the given class.
+    ///
+    /// Relative attenuation is measured against the attenuation at the exact midband frequency, and checked at every
+    /// breakpoint of the tolerance mask, on both sides of the band. A negative margin means the band does not comply.
+    /// Breakpoints at or above 1/2 the sampling frequency are skipped.
+    pub fn compliance(&self, class: OctaveFilterClass) -> Vec<T>
+    {
+        let one = T::one();
+        let g = Self::octave_ratio();
+        let b = <T as NumCast>::from(self.fraction.bands_per_octave()).unwrap();
+        let two = one + one;
+        let nyquist = self.sampling_frequency/two;
+        let (lo, hi) = match class
+        {
+            OctaveFilterClass::Class1 => (0, 1),
+            OctaveFilterClass::Class2 => (2, 3)
+        };
+
+        let reference: Vec<T> = self.bands.iter()
+            .map(|band| self.magnitude(band.center_frequency))
+            .enumerate()
+            .map(|(i, h)| h[i])
+            .collect();
+
+        let mut margin = vec![T::infinity(); self.bands.len()];
+        for &(e, limits) in MASK.iter()
+        {
+            // Breakpoints of fractional-octave filters are scaled according to the bandwidth designator.
+            let omega_1 = g.powf(<T as NumCast>::from(e).unwrap());
+            let omega_b = one + (g.powf((two*b).recip()) - one)/(g.powf(two.recip()) - one)*(omega_1 - one);
+            let min = <T as NumCast>::from(limits[lo]).unwrap();
+            let max = <T as NumCast>::from(limits[hi]).unwrap();
+
+            for (i, band) in self.bands.iter()
+                .enumerate()
+            {
+                for f in [band.center_frequency*omega_b, band.center_frequency/omega_b]
+                {
+                    if !(f < nyquist)
+                    {
+                        continue
+                    }
+                    let h = self.magnitude(f)[i];
+                    let attenuation = if h.is_zero()
+                    {
+                        T::infinity()
+                    }
+                    else
+                    {
+                        -<T as NumCast>::from(20.0).unwrap()*(h/reference[i]).log10()
+                    };
+                    let m = (attenuation - min).min(max - attenuation);
+                    if m < margin[i]
+                    {
+                        margin[i] = m
+                    }
+                }
+            }
+        }
+
+        margin
+    }
+
+    pub fn is_compliant(&self, class: OctaveFilterClass) -> bool
+    {
+        self.compliance(class)
+            .into_iter()
+            .all(|m| m >= T::zero())
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use crate::gen::filter::{OctaveFilterBank, OctaveFilterClass, OctaveFraction};
+
+    #[test]
+    fn test()
+    {
+        let bank = OctaveFilterBank::<f64>::new(OctaveFraction::Third, [25.0, 10000.0], 3, 48000.0)
+            .unwrap();
+
+        assert_eq!(bank.bands.len(), 27);
+        assert!((bank.bands.iter().find(|band| band.index == 0).unwrap().center_frequency - 1000.0).abs() < 1e-9);
+
+        // Every band meets class 1, with the tightest margin being the 0.4 dB tolerance at midband.
+        let margin = bank.compliance(OctaveFilterClass::Class1);
+        assert_eq!(margin.len(), bank.bands.len());
+        assert!(margin.iter().all(|&m| (m - 0.4).abs() < 1e-6));
+        assert!(bank.is_compliant(OctaveFilterClass::Class1));
+        assert!(bank.is_compliant(OctaveFilterClass::Class2));
+
+        // A first-order design is far too wide, and violates both classes in every band.
+        let bank = OctaveFilterBank::<f64>::new(OctaveFraction::Third, [25.0, 10000.0], 1, 48000.0)
+            .unwrap();
+
+        assert!(bank.compliance(OctaveFilterClass::Class1).into_iter().all(|m| m < -10.0));
+        assert!(!bank.is_compliant(OctaveFilterClass::Class1));
+        assert!(!bank.is_compliant(OctaveFilterClass::Class2));
+    }
+}
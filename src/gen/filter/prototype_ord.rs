@@ -0,0 +1,171 @@
+use core::ops::{DivAssign, MulAssign};
+
+use array_math::{ArrayMath, ArrayOps};
+use num::{traits::FloatConst, Complex, Float};
+
+use crate::{validate_filter_bands, gen::filter::{FilterBandError, FilterGenPlane, FilterGenType}};
+
+const MAX_ORDER: usize = 64;
+
+/// Frequency at which the all-pole prototype with unit DC gain and poles `p` has the squared magnitude `mag2`.
+pub(crate) fn attenuation_frequency<T>(p: &[Complex<T>], mag2: T) -> T
+where
+    T: Float
+{
+    let one = T::one();
+    let two = one + one;
+
+    let h2 = |w: T| p.iter()
+        .map(|&p| (p.norm_sqr(), (Complex::new(T::zero(), w) - p).norm_sqr()))
+        .fold(one, |m, (p, d)| m*p/d);
+
+    let mut lo = T::zero();
+    let mut hi = one;
+    while h2(hi) > mag2 && hi < T::max_value()/two
+    {
+        lo = hi;
+        hi = hi*two
+    }
+    for _ in 0..128
+    {
+        let mid = (lo + hi)/two;
+        if h2(mid) > mag2
+        {
+            lo = mid
+        }
+        else
+        {
+            hi = mid
+        }
+    }
+    (lo + hi)/two
+}
+
+/// Order estimation for all-pole prototypes without a closed-form order formula.
+///
+/// The smallest order whose transition ratio meets the specification is found by evaluating the prototype directly, up to an
+/// order of 64.
+/// The natural frequencies are chosen such that the passband specification is met exactly.
+pub(crate) fn prototype_ord<T, P, const F: usize>(
+    mut passband_frequencies: [T; F],
+    mut stopband_frequencies: [T; F],
+    passband_ripple: T,
+    stopband_attenuation: T,
+    plane: FilterGenPlane<T>,
+    prototype: P
+) -> Result<(usize, [T; F], FilterGenType), FilterBandError>
+where
+    T: Float + FloatConst + MulAssign + DivAssign,
+    P: Fn(usize) -> Vec<Complex<T>>,
+    [(); F - 1]:,
+    [(); 2 - F]:
+{
+    let one = T::one();
+    let two = one + one;
+    let four = two + two;
+    let ten = T::from(10.0).unwrap();
+
+    let t = if let FilterGenPlane::Z { sampling_frequency } = plane
+    {
+        let t = sampling_frequency.unwrap_or(two);
+        for wc in passband_frequencies.iter_mut()
+            .chain(stopband_frequencies.iter_mut())
+        {
+            if *wc > t/two
+            {
+                return Err(FilterBandError::EdgesOutOfRange)
+            }
+            *wc = two/t*(T::PI()**wc/t).tan()
+        }
+        Some(t)
+    }
+    else
+    {
+        None
+    };
+
+    validate_filter_bands(&passband_frequencies, &stopband_frequencies, t)?;
+
+    let filter_type;
+    let ratio;
+
+    let pp = passband_frequencies.product();
+    let ps = stopband_frequencies.product();
+
+    if F == 2
+    {
+        if passband_frequencies[0] > stopband_frequencies[0]
+        {
+            filter_type = FilterGenType::BandPass;
+
+            if pp < ps
+            {
+                stopband_frequencies[1] = pp/stopband_frequencies[0]
+            }
+            else
+            {
+                stopband_frequencies[0] = pp/stopband_frequencies[1]
+            }
+
+            ratio = (stopband_frequencies[1] - stopband_frequencies[0])/(passband_frequencies[1] - passband_frequencies[0]);
+        }
+        else
+        {
+            filter_type = FilterGenType::BandStop;
+
+            if pp > ps
+            {
+                passband_frequencies[1] = ps/passband_frequencies[0]
+            }
+            else
+            {
+                passband_frequencies[0] = ps/passband_frequencies[1]
+            }
+
+            ratio = (passband_frequencies[1] - passband_frequencies[0])/(stopband_frequencies[1] - stopband_frequencies[0]);
+        }
+    }
+    else if passband_frequencies[0] > stopband_frequencies[0]
+    {
+        filter_type = FilterGenType::HighPass;
+
+        ratio = passband_frequencies[0]/stopband_frequencies[0];
+    }
+    else
+    {
+        filter_type = FilterGenType::LowPass;
+
+        ratio = stopband_frequencies[0]/passband_frequencies[0];
+    }
+
+    let mag2_p = ten.powf(-passband_ripple.abs()/ten);
+    let mag2_s = ten.powf(-stopband_attenuation.abs()/ten);
+
+    let (n, wp) = (1..=MAX_ORDER).map(|n| {
+            let p = prototype(n);
+            (n, attenuation_frequency(&p, mag2_p), attenuation_frequency(&p, mag2_s))
+        }).find(|&(_, wp, ws)| ws/wp <= ratio)
+        .map(|(n, wp, _)| (n, wp))
+        .ok_or(FilterBandError::OrderTooHigh)?;
+
+    let wn = match filter_type
+    {
+        FilterGenType::LowPass => passband_frequencies.map(|w| w/wp),
+        FilterGenType::HighPass => passband_frequencies.map(|w| w*wp),
+        FilterGenType::BandPass | FilterGenType::BandStop => {
+            let bw = passband_frequencies[1] - passband_frequencies[0];
+            let b = if filter_type == FilterGenType::BandPass {bw/wp} else {bw*wp};
+            let s = (b*b + four*passband_frequencies.product()).sqrt();
+            [(s - b)/two, (s + b)/two].try_reformulate_length().map_err(|_| ()).unwrap()
+        }
+    };
+
+    if let Some(t) = t
+    {
+        Ok((n, wn.map(|w| (w*t/two).atan()*two/T::PI()), filter_type))
+    }
+    else
+    {
+        Ok((n, wn, filter_type))
+    }
+}
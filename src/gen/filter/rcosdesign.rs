@@ -0,0 +1,211 @@
+use num::{traits::FloatConst, Float, NumCast, Zero};
+use thiserror::Error;
+
+use crate::{System, systems::Tf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum PulseShapeError
+{
+    #[error("Roll-off factor must be a number in the range [0.0, 1.0].")]
+    RollOffOutOfRange,
+    #[error("Bandwidth-time product must be a positive number.")]
+    BandwidthTimeProductOutOfRange,
+    #[error("Filter span must be at least one symbol.")]
+    ZeroSpan,
+    #[error("There must be at least one sample per symbol.")]
+    ZeroSamplesPerSymbol,
+    #[error("Filter order, span times samples per symbol, must be even.")]
+    OddOrder
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RCosShape
+{
+    Normal,
+    Sqrt
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PulseNormalization
+{
+    Energy,
+    Peak,
+    Gain
+}
+
+impl PulseNormalization
+{
+    pub(crate) fn normalize<T>(self, b: &mut [T])
+    where
+        T: Float
+    {
+        let s = match self
+        {
+            PulseNormalization::Energy => b.iter()
+                .map(|&b| b*b)
+                .fold(T::zero(), |a, b| a + b)
+                .sqrt(),
+            PulseNormalization::Peak => b.iter()
+                .map(|&b| b.abs())
+                .fold(T::zero(), Float::max),
+            PulseNormalization::Gain => b.iter()
+                .copied()
+                .fold(T::zero(), |a, b| a + b)
+        };
+        if !s.is_zero()
+        {
+            for b in b.iter_mut()
+            {
+                *b = *b/s
+            }
+        }
+    }
+}
+
+pub trait RCosDesign: System + Sized
+where
+    Self::Set: Float
+{
+    fn rcosdesign(
+        roll_off: Self::Set,
+        span: usize,
+        samples_per_symbol: usize,
+        shape: RCosShape,
+        normalization: PulseNormalization
+    ) -> Result<Self, PulseShapeError>;
+}
+
+impl<T> RCosDesign for Tf<T, Vec<T>, ()>
+where
+    T: Float + FloatConst
+{
+    fn rcosdesign(
+        beta: T,
+        span: usize,
+        sps: usize,
+        shape: RCosShape,
+        normalization: PulseNormalization
+    ) -> Result<Self, PulseShapeError>
+    {
+        let zero = T::zero();
+        let one = T::one();
+        let two = one + one;
+        let four = two + two;
+
+        if !(beta >= zero && beta <= one)
+        {
+            return Err(PulseShapeError::RollOffOutOfRange)
+        }
+        if span == 0
+        {
+            return Err(PulseShapeError::ZeroSpan)
+        }
+        if sps == 0
+        {
+            return Err(PulseShapeError::ZeroSamplesPerSymbol)
+        }
+        let order = span*sps;
+        if order % 2 != 0
+        {
+            return Err(PulseShapeError::OddOrder)
+        }
+
+        let delay = order/2;
+        let spsf = <T as NumCast>::from(sps).unwrap();
+        let tol = T::epsilon().sqrt();
+        let pi = T::PI();
+
+        let sinc = |x: T| if x.is_zero()
+        {
+            one
+        }
+        else
+        {
+            (pi*x).sin()/(pi*x)
+        };
+
+        let mut b: Vec<T> = (0..=order).map(|i| {
+                let t = (<T as NumCast>::from(i).unwrap() - <T as NumCast>::from(delay).unwrap())/spsf;
+                if beta.is_zero()
+                {
+                    return sinc(t)
+                }
+                match shape
+                {
+                    RCosShape::Normal => {
+                        let denom = one - (two*beta*t).powi(2);
+                        if denom.abs() < tol
+                        {
+                            beta*(pi/(two*beta)).sin()/two
+                        }
+                        else
+                        {
+                            sinc(t)*(pi*beta*t).cos()/denom
+                        }
+                    },
+                    RCosShape::Sqrt => {
+                        if t.is_zero()
+                        {
+                            -(pi*(beta - one) - four*beta)/(pi*spsf)
+                        }
+                        else if ((four*beta*t).abs() - one).abs() < tol
+                        {
+                            (
+                                pi*(beta + one)*(pi*(beta + one)/(four*beta)).sin()
+                                - four*beta*(pi*(beta - one)/(four*beta)).sin()
+                                + pi*(beta - one)*(pi*(beta - one)/(four*beta)).cos()
+                            )/(two*pi*spsf)
+                        }
+                        else
+                        {
+                            -four*beta/spsf*(((one + beta)*pi*t).cos() + ((one - beta)*pi*t).sin()/(four*beta*t))
+                                /(pi*((four*beta*t).powi(2) - one))
+                        }
+                    }
+                }
+            }).collect();
+
+        normalization.normalize(&mut b);
+
+        Ok(Tf::new(b, ()))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use array_math::ArrayOps;
+
+    use crate::{plot, gen::filter::{PulseNormalization, RCosDesign, RCosShape}, analysis::RealFreqZ, systems::Tf};
+
+    #[test]
+    fn test()
+    {
+        const SPAN: usize = 6;
+        const SPS: usize = 4;
+        let h = Tf::<f64, Vec<_>, ()>::rcosdesign(0.25, SPAN, SPS, RCosShape::Sqrt, PulseNormalization::Energy)
+            .unwrap();
+        let energy: f64 = h.b.iter()
+            .map(|b| b*b)
+            .sum();
+        assert!((energy - 1.0).abs() < 1e-12);
+
+        let g = Tf::<f64, Vec<_>, ()>::rcosdesign(0.25, SPAN, SPS, RCosShape::Normal, PulseNormalization::Peak)
+            .unwrap();
+        for (i, &g) in g.b.iter()
+            .enumerate()
+        {
+            if i != SPAN*SPS/2 && i % SPS == 0
+            {
+                assert!(g.abs() < 1e-12)
+            }
+        }
+
+        const N: usize = 1024;
+        let (h_f, w): ([_; N], _) = h.real_freqz(());
+        let (g_f, _): ([_; N], _) = g.real_freqz(());
+
+        plot::plot_curves("H(e^jw)", "plots/h_z_rcosdesign.png", [&w.zip(h_f.map(|h| h.norm())), &w.zip(g_f.map(|g| g.norm()))])
+            .unwrap();
+    }
+}
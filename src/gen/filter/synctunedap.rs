@@ -0,0 +1,71 @@
+use num::{traits::FloatConst, Complex, Float, NumCast};
+use option_trait::Maybe;
+
+use crate::{quantities::ProductSequence, System, systems::Zpk};
+
+pub trait SyncTunedAP<O>: System + Sized
+where
+    Self::Set: Float,
+    O: Maybe<usize>
+{
+    /// Cascade of identical real first-order sections, scaled to -3 dB at 1 rad/s.
+    fn synctunedap(order: O) -> Self;
+}
+
+impl<T> SyncTunedAP<usize> for Zpk<Complex<T>, (), Vec<Complex<T>>, T>
+where
+    T: Float + FloatConst
+{
+    fn synctunedap(order: usize) -> Self
+    {
+        if order == 0
+        {
+            return Self::one()
+        }
+
+        let one = T::one();
+        let two = one + one;
+        let n = <T as NumCast>::from(order).unwrap();
+        let a = (two.powf(n.recip()) - one).sqrt().recip();
+
+        Zpk {
+            z: ProductSequence::new(()),
+            p: ProductSequence::new(vec![Complex::from(-a); order]),
+            k: a.powi(order as i32)
+        }
+    }
+}
+impl<T, const N: usize> SyncTunedAP<()> for Zpk<Complex<T>, (), [Complex<T>; N], T>
+where
+    T: Float + FloatConst,
+    Zpk<Complex<T>, (), Vec<Complex<T>>, T>: SyncTunedAP<usize> + System<Set = T>
+{
+    fn synctunedap((): ()) -> Self
+    {
+        let Zpk { z, p, k } = Zpk::synctunedap(N);
+
+        Zpk {
+            z,
+            p: p.try_into().map_err(|_| ()).unwrap(),
+            k
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use num::Complex;
+
+    use crate::{gen::filter::SyncTunedAP, analysis::FreqS, systems::Zpk};
+
+    #[test]
+    fn test()
+    {
+        let h = Zpk::synctunedap(4);
+
+        let [h_0, h_1] = h.freqs([Complex::new(0.0, 0.0), Complex::new(0.0, 1.0)]);
+        assert!((h_0.norm() - 1.0).abs() < 1e-9);
+        assert!((h_1.norm() - 0.5f64.sqrt()).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,287 @@
+
+
+use ndarray::Array2;
+use num::{complex::ComplexFloat, traits::FloatConst, Complex, Float};
+use option_trait::Maybe;
+
+use crate::{gen::filter::{TransitionalAP, TransitionalType, FilterGenError, FilterGenPlane, FilterGenType}, transforms::{domain::Bilinear, filter::SfTrans, system::{ToSos, ToSs, ToTf}}, systems::{Sos, Ss, SsAMatrix, SsBMatrix, SsCMatrix, SsDMatrix, Tf, Zpk}, System};
+
+pub trait Transitional<O>: System + Sized
+where
+    O: Maybe<usize>
+{
+    fn transitional<const F: usize>(
+        order: O,
+        transitional_type: TransitionalType<<Self::Set as ComplexFloat>::Real>,
+        frequencies: [<Self::Set as ComplexFloat>::Real; F],
+        filter_type: FilterGenType,
+        plane: FilterGenPlane<<Self::Set as ComplexFloat>::Real>
+    ) -> Result<Self, FilterGenError>
+    where
+        [(); F - 1]:,
+        [(); 2 - F]:;
+}
+
+impl<T> Transitional<usize> for Zpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T>
+where
+    T: Float + FloatConst,
+    Complex<T>: ComplexFloat<Real = T>,
+    Zpk<Complex<T>, (), Vec<Complex<T>>, T>: TransitionalAP<usize> + SfTrans<1, Output = Self> + SfTrans<2, Output = Self> + System<Set = T>,
+    Self: Bilinear<Output = Self> + System<Set = T>
+{
+    fn transitional<const F: usize>(
+        order: usize,
+        transitional_type: TransitionalType<T>,
+        mut frequencies: [T; F],
+        filter_type: FilterGenType,
+        plane: FilterGenPlane<T>
+    ) -> Result<Self, FilterGenError>
+    where
+        [(); F - 1]:,
+        [(); 2 - F]:
+    {
+        if order < 1
+        {
+            return Err(FilterGenError::ZeroOrder)
+        }
+        if !frequencies.is_sorted()
+        {
+            return Err(FilterGenError::FrequenciesNotNondecreasing)
+        }
+        let stop = match filter_type
+        {
+            FilterGenType::LowPass => false,
+            FilterGenType::HighPass => true,
+            FilterGenType::BandPass => false,
+            FilterGenType::BandStop => true,
+        };
+        let band = match filter_type
+        {
+            FilterGenType::LowPass => false,
+            FilterGenType::HighPass => false,
+            FilterGenType::BandPass => true,
+            FilterGenType::BandStop => true
+        };
+        let one = T::one();
+        let two = one + one;
+        let t = if let FilterGenPlane::Z { sampling_frequency } = plane
+        {
+            let t = sampling_frequency.unwrap_or(two);
+            for wc in frequencies.iter_mut()
+            {
+                if *wc > t/two
+                {
+                    return Err(FilterGenError::FrequenciesOutOfRange)
+                }
+                *wc = two/t*(T::PI()**wc/t).tan()
+            }
+            Some(t)
+        }
+        else
+        {
+            None
+        };
+    
+        let zpk = Zpk::transitionalap(order, transitional_type);
+    
+        let zpk = if !band && F == 2
+        {
+            SfTrans::<1>::sftrans(zpk, one, [frequencies[!stop as usize]], stop).unwrap()
+        }
+        else if band && F == 1
+        {
+            SfTrans::<2>::sftrans(zpk, one, [frequencies[0], frequencies[0]], stop).unwrap()
+        }
+        else if F == 1
+        {
+            SfTrans::<1>::sftrans(zpk, one, [frequencies[0]], stop).unwrap()
+        }
+        else
+        {
+            SfTrans::<2>::sftrans(zpk, one, [frequencies[0], frequencies[1]], stop).unwrap()
+        };
+
+        if let Some(t) = t
+        {
+            Ok(zpk.bilinear(t.recip()).unwrap())
+        }
+        else
+        {
+            Ok(zpk)
+        }
+    }
+}
+
+impl<T> Transitional<usize> for Tf<T, Vec<T>, Vec<T>>
+where
+    T: Float + FloatConst,
+    Zpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T>: Transitional<usize> + ToTf<T, Vec<T>, Vec<T>, (), ()> + System<Set = T>
+{
+    fn transitional<const F: usize>(
+        order: usize,
+        transitional_type: TransitionalType<T>,
+        frequencies: [T; F],
+        filter_type: FilterGenType,
+        plane: FilterGenPlane<T>
+    ) -> Result<Self, FilterGenError>
+    where
+        [(); F - 1]:,
+        [(); 2 - F]:
+    {
+        let zpk = Zpk::transitional(order, transitional_type, frequencies, filter_type, plane)?;
+    
+        Ok(zpk.to_tf((), ()))
+    }
+}
+
+impl<T, const N: usize> Transitional<()> for Tf<T, [T; N], [T; N]>
+where
+    [(); N - 2]:,
+    T: Float + FloatConst,
+    Tf<T, Vec<T>, Vec<T>>: Transitional<usize> + System<Set = T>
+{
+    fn transitional<const F: usize>(
+        (): (),
+        transitional_type: TransitionalType<T>,
+        frequencies: [T; F],
+        filter_type: FilterGenType,
+        plane: FilterGenPlane<T>
+    ) -> Result<Self, FilterGenError>
+    where
+        [(); F - 1]:,
+        [(); 2 - F]:
+    {
+        let tf = Tf::transitional(N - 1, transitional_type, frequencies, filter_type, plane)?;
+
+        Ok(tf.truncate())
+    }
+}
+
+impl<T> Transitional<usize> for Sos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>>
+where
+    T: Float + FloatConst,
+    Zpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T>: Transitional<usize> + ToSos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>, (), ()> + System<Set = T>
+{
+    fn transitional<const F: usize>(
+        order: usize,
+        transitional_type: TransitionalType<T>,
+        frequencies: [T; F],
+        filter_type: FilterGenType,
+        plane: FilterGenPlane<T>
+    ) -> Result<Self, FilterGenError>
+    where
+        [(); F - 1]:,
+        [(); 2 - F]:
+    {
+        let zpk = Zpk::transitional(order, transitional_type, frequencies, filter_type, plane)?;
+    
+        Ok(zpk.to_sos((), ()))
+    }
+}
+
+impl<T, const N: usize> Transitional<()> for Sos<T, [T; 3], [T; 3], [Tf<T, [T; 3], [T; 3]>; N]>
+where
+    T: Float + FloatConst,
+    Sos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>>: Transitional<usize> + System<Set = T>
+{
+    fn transitional<const F: usize>(
+        (): (),
+        transitional_type: TransitionalType<T>,
+        frequencies: [T; F],
+        filter_type: FilterGenType,
+        plane: FilterGenPlane<T>
+    ) -> Result<Self, FilterGenError>
+    where
+        [(); F - 1]:,
+        [(); 2 - F]:
+    {
+        let sos = Sos::transitional(N*2, transitional_type, frequencies, filter_type, plane)?;
+
+        Ok(Sos {
+            sos: sos.sos.try_into().map_err(|_| ()).unwrap()
+        })
+    }
+}
+
+impl<T> Transitional<usize> for Ss<T, Array2<T>, Array2<T>, Array2<T>, Array2<T>>
+where
+    T: Float + FloatConst,
+    Zpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T>: Transitional<usize> + ToSs<T, Array2<T>, Array2<T>, Array2<T>, Array2<T>> + System<Set = T>,
+    Array2<T>: SsAMatrix<T, Array2<T>, Array2<T>, Array2<T>> + SsBMatrix<T, Array2<T>, Array2<T>, Array2<T>> + SsCMatrix<T, Array2<T>, Array2<T>, Array2<T>>+ SsDMatrix<T, Array2<T>, Array2<T>, Array2<T>>
+{
+    fn transitional<const F: usize>(
+        order: usize,
+        transitional_type: TransitionalType<T>,
+        frequencies: [T; F],
+        filter_type: FilterGenType,
+        plane: FilterGenPlane<T>
+    ) -> Result<Self, FilterGenError>
+    where
+        [(); F - 1]:,
+        [(); 2 - F]:
+    {
+        let zpk = Zpk::transitional(order, transitional_type, frequencies, filter_type, plane)?;
+    
+        Ok(zpk.to_ss())
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::PI;
+
+    use array_math::ArrayOps;
+    use num::Complex;
+    
+    use crate::{plot, gen::filter::{Transitional, TransitionalType, FilterGenPlane}, Plane, analysis::RealFreqZ, systems::Tf, transforms::system::ToZpk, systems::Zpk};
+
+    #[test]
+    fn test()
+    {
+        let fs = 1000.0;
+
+        let transitional_type = TransitionalType::ButterworthThomson { m: 0.5 };
+
+        let (n, wn, t) = crate::gen::filter::transitionalord(
+            [40.0],
+            [150.0],
+            3.0,
+            60.0,
+            transitional_type,
+            FilterGenPlane::Z { sampling_frequency: Some(fs) }
+        ).unwrap();
+
+        let h = Tf::transitional(n, transitional_type, wn, t, FilterGenPlane::Z { sampling_frequency: None })
+            .unwrap();
+
+        const N: usize = 1024;
+        let (h_f, w): ([_; N], _) = h.real_freqz(());
+
+        plot::plot_curves("H(e^jw)", "plots/h_z_transitional.png", [&w.zip(h_f.map(|h| h.norm())), &w.zip(h_f.map(|h| h.arg()))])
+            .unwrap();
+
+        let h: Zpk<_, Vec<_>, Vec<_>, _> = h.to_zpk((), ());
+
+        plot::plot_pz("H(z)", "plots/pz_z_transitional.png", &h.p, &h.z, Plane::Z)
+            .unwrap();
+
+        // Magnitude response, with the frequency normalized to the Nyquist frequency.
+        let mag = |w: f64| {
+            let z = Complex::cis(PI*w);
+            (h.k*h.z.iter().map(|&zz| z - zz).product::<Complex<f64>>()/h.p.iter().map(|&p| z - p).product::<Complex<f64>>()).norm()
+        };
+        let db = |w: f64| 20.0*mag(w).log10();
+
+        assert!((mag(0.0) - 1.0).abs() < 1e-6);
+        // The passband edge is attenuated by exactly the passband ripple, and the stopband edge by at least the stopband attenuation.
+        assert!((db(40.0/(fs/2.0)) + 3.0).abs() < 0.01);
+        assert!(db(150.0/(fs/2.0)) <= -60.0);
+        // The natural frequency is close to the -3 dB point of the prototype.
+        assert!((db(wn[0]) + 3.0).abs() < 0.3);
+        // Monotonic magnitude response.
+        let m: Vec<_> = (0..=1000).map(|i| mag(i as f64/1000.0))
+            .collect();
+        assert!(m.windows(2).all(|m| m[1] <= m[0] + 1e-9));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,208 @@
+use core::ops::{AddAssign, DivAssign, MulAssign, SubAssign};
+
+use array_math::SliceMath;
+use num::{complex::ComplexFloat, traits::FloatConst, Complex, Float, NumCast};
+use option_trait::Maybe;
+
+use crate::{gen::filter::{BesselAP, BesselNorm, ButtAP}, quantities::ProductSequence, System, systems::Zpk};
+
+/// Family of transitional prototypes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransitionalType<T>
+{
+    /// Butterworth-Thomson, with the poles interpolated geometrically between the Butterworth poles (`m = 0`) and the
+    /// magnitude-normalized Bessel-Thomson poles (`m = 1`).
+    ButterworthThomson {
+        m: T
+    },
+    /// Gaussian-to-6dB, following a Gaussian magnitude response down to -6 dB, and falling off faster than the Gaussian
+    /// prototype of the same order beyond.
+    Gaussian6dB
+}
+
+pub trait TransitionalAP<O>: System + Sized
+where
+    Self::Set: Float,
+    O: Maybe<usize>
+{
+    /// Transitional prototype.
+    ///
+    /// Every member of either family has its -3 dB point close to 1 rad/s.
+    fn transitionalap(order: O, transitional_type: TransitionalType<Self::Set>) -> Self;
+}
+
+fn butterworth_thomson<T>(order: usize, m: T) -> Vec<Complex<T>>
+where
+    T: Float,
+    Zpk<Complex<T>, (), Vec<Complex<T>>, T>: ButtAP<usize> + BesselAP<usize>
+{
+    let one = T::one();
+    let m = m.max(T::zero()).min(one);
+
+    let by_angle = |p: &ProductSequence<Complex<T>, Vec<Complex<T>>>| {
+        let mut p = p.to_vec();
+        p.sort_by(|a, b| a.im.partial_cmp(&b.im).unwrap());
+        p
+    };
+    let pb = by_angle(&Zpk::<Complex<T>, (), Vec<Complex<T>>, T>::buttap(order).p);
+    let pt = by_angle(&Zpk::<Complex<T>, (), Vec<Complex<T>>, T>::besselap_norm(order, BesselNorm::Magnitude).p);
+
+    pb.into_iter()
+        .zip(pt)
+        .map(|(pb, pt)| {
+            let (rb, tb) = pb.to_polar();
+            let (rt, tt) = pt.to_polar();
+            Complex::from_polar(rb.powf(one - m)*rt.powf(m), tb*(one - m) + tt*m)
+        }).collect()
+}
+
+impl<T> TransitionalAP<usize> for Zpk<Complex<T>, (), Vec<Complex<T>>, T>
+where
+    T: Float + FloatConst + AddAssign + MulAssign + Into<Complex<T>> + ComplexFloat<Real = T> + ndarray_linalg::Lapack<Real = T, Complex = Complex<T>>,
+    Complex<T>: From<T> + AddAssign + SubAssign + MulAssign + DivAssign + DivAssign<T>,
+    Self: ButtAP<usize> + BesselAP<usize>
+{
+    fn transitionalap(order: usize, transitional_type: TransitionalType<T>) -> Self
+    {
+        if order == 0
+        {
+            return Self::one()
+        }
+
+        let zero = T::zero();
+        let one = T::one();
+
+        let p = match transitional_type
+        {
+            TransitionalType::ButterworthThomson { m } => butterworth_thomson(order, m),
+            TransitionalType::Gaussian6dB => {
+                // 1/|H(jw)|^2 = P(y), y = ln(2)*w^2, is the Taylor series of exp(y) up to the second highest power, with the
+                // highest coefficient chosen such that P(ln(4)) = 4, i.e. exactly -6 dB where the Gaussian is.
+                let two = one + one;
+                let ln2 = Float::ln(two);
+                let y6 = ln2 + ln2;
+                let mut c = vec![one];
+                for i in 1..order
+                {
+                    c.push(c[i - 1]/<T as NumCast>::from(i).unwrap())
+                }
+                let taylor = c.iter()
+                    .rev()
+                    .fold(zero, |y, &c| y*y6 + c);
+                c.push((two + two - taylor)/Float::powi(y6, order as i32));
+
+                // P(-ln(2)*s^2), in descending powers of s.
+                let mut q = vec![zero; 2*order + 1];
+                let mut a = one;
+                for (i, &c) in c.iter()
+                    .enumerate()
+                {
+                    q[2*i] = c*a;
+                    a = -a*ln2
+                }
+                q.reverse();
+
+                let r: Vec<Complex<T>> = q.rpolynomial_roots();
+                let mut p: Vec<Complex<T>> = r.into_iter()
+                    .filter(|p| p.re < zero)
+                    .collect();
+                p.truncate(order);
+                p
+            }
+        };
+
+        let k = p.iter()
+            .map(|&p| -p)
+            .product::<Complex<T>>()
+            .re;
+
+        Zpk {
+            z: ProductSequence::new(()),
+            p: ProductSequence::new(p),
+            k
+        }
+    }
+}
+impl<T, const N: usize> TransitionalAP<()> for Zpk<Complex<T>, (), [Complex<T>; N], T>
+where
+    T: Float + FloatConst,
+    Zpk<Complex<T>, (), Vec<Complex<T>>, T>: TransitionalAP<usize> + System<Set = T>
+{
+    fn transitionalap((): (), transitional_type: TransitionalType<T>) -> Self
+    {
+        let Zpk { z, p, k } = Zpk::transitionalap(N, transitional_type);
+
+        Zpk {
+            z,
+            p: p.try_into().map_err(|_| ()).unwrap(),
+            k
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use array_math::ArrayOps;
+    use linspace::LinspaceArray;
+    use num::Complex;
+
+    use crate::{plot, gen::filter::{attenuation_frequency, TransitionalAP, TransitionalType}, analysis::FreqS, systems::Zpk, Plane};
+
+    #[test]
+    fn test()
+    {
+        let h = Zpk::transitionalap(6, TransitionalType::ButterworthThomson { m: 0.5 });
+
+        plot::plot_pz("H(s)", "plots/pz_s_transitionalap.png", h.poles(), h.zeros(), Plane::S)
+            .unwrap();
+
+        const N: usize = 1024;
+        let w: [_; N] = (0.0..2.0).linspace_array();
+        let h_f = h.freqs(w.map(|w| Complex::new(0.0, w)));
+
+        plot::plot_curves("H(jw)", "plots/h_s_transitionalap.png", [&w.zip(h_f.map(|h| h.norm())), &w.zip(h_f.map(|h| h.arg()))])
+            .unwrap();
+
+        // Unit DC gain, and a monotonically decreasing magnitude with the -3 dB point within 2 % of 1 rad/s.
+        assert!((h_f[0].norm() - 1.0).abs() < 1e-9);
+        assert!(h_f.windows(2).all(|h| h[1].norm() <= h[0].norm() + 1e-9));
+        let w_3db = attenuation_frequency(&h.p, 0.5);
+        assert!((w_3db - 1.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_gaussian()
+    {
+        const ORDER: usize = 6;
+
+        let h = Zpk::transitionalap(ORDER, TransitionalType::Gaussian6dB);
+
+        const N: usize = 64;
+        let w: [_; N] = (0.0..3.0).linspace_array();
+        let h_f = h.freqs(w.map(|w| Complex::new(0.0, w)));
+
+        let ln2 = 2f64.ln();
+        for (&w, h) in w.iter()
+            .zip(h_f)
+        {
+            let y = ln2*w*w;
+            let gaussian = (-y/2.0).exp();
+            let taylor = (0..=ORDER).map(|k| y.powi(k as i32)/(1..=k).product::<usize>() as f64)
+                .sum::<f64>();
+
+            // Gaussian down to -6 dB, then falling off faster than the Gaussian prototype of the same order.
+            if w*w <= 2.0
+            {
+                assert!((h.norm() - gaussian).abs() < 1e-2);
+            }
+            else
+            {
+                assert!(h.norm() <= taylor.sqrt().recip());
+            }
+        }
+
+        let h_6db = h.freqs([Complex::new(0.0, 2f64.sqrt())]);
+        assert!((h_6db[0].norm() - 0.5).abs() < 1e-6);
+    }
+}
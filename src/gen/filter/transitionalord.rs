@@ -0,0 +1,30 @@
+use core::ops::{DivAssign, MulAssign};
+
+use num::{traits::FloatConst, Complex, Float};
+
+use crate::{gen::filter::{prototype_ord, TransitionalAP, TransitionalType, FilterBandError, FilterGenPlane, FilterGenType}, systems::Zpk};
+
+pub fn transitionalord<T, const F: usize>(
+    passband_frequencies: [T; F],
+    stopband_frequencies: [T; F],
+    passband_ripple: T,
+    stopband_attenuation: T,
+    transitional_type: TransitionalType<T>,
+    plane: FilterGenPlane<T>
+) -> Result<(usize, [T; F], FilterGenType), FilterBandError>
+where
+    T: Float + FloatConst + MulAssign + DivAssign,
+    Zpk<Complex<T>, (), Vec<Complex<T>>, T>: TransitionalAP<usize>,
+    [(); F - 1]:,
+    [(); 2 - F]:
+{
+    prototype_ord(
+        passband_frequencies,
+        stopband_frequencies,
+        passband_ripple,
+        stopband_attenuation,
+        plane,
+        |n| Zpk::<Complex<T>, (), Vec<Complex<T>>, T>::transitionalap(n, transitional_type).p
+            .to_vec()
+    )
+}
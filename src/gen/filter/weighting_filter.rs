@@ -0,0 +1,181 @@
+use num::{complex::ComplexFloat, traits::FloatConst, Complex, Float, NumCast};
+
+use crate::{gen::filter::{FilterGenError, FilterGenPlane}, transforms::system::{ToSos, ToTf}, systems::{Sos, Tf, Zpk}, System};
+
+/// Pole frequencies of IEC 61672-1 (annex E), in Hz.
+const F1: f64 = 20.598997;
+const F2: f64 = 107.65265;
+const F3: f64 = 737.86223;
+const F4: f64 = 12194.217;
+
+/// Frequency at which the weightings are normalized to 0 dB.
+const F_REF: f64 = 1000.0;
+
+/// Frequency weightings of IEC 61672-1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrequencyWeighting
+{
+    A,
+    C,
+    Z
+}
+
+pub trait WeightingFilter: System + Sized
+{
+    /// Frequency weighting filter, normalized to unit gain at 1 kHz.
+    ///
+    /// The digital filter maps the low-frequency poles with the prewarped bilinear transform. Poles above 1/8 the sampling
+    /// frequency would be squeezed towards Nyquist by the bilinear transform, attenuating the top octaves, so they are mapped
+    /// with the matched z-transform instead.
+    fn weighting_filter(weighting: FrequencyWeighting, plane: FilterGenPlane<<Self::Set as ComplexFloat>::Real>) -> Result<Self, FilterGenError>;
+}
+
+impl<T> WeightingFilter for Zpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T>
+where
+    T: Float + FloatConst,
+    Self: System<Set = T>
+{
+    fn weighting_filter(weighting: FrequencyWeighting, plane: FilterGenPlane<T>) -> Result<Self, FilterGenError>
+    {
+        let zero = T::zero();
+        let one = T::one();
+        let two = one + one;
+        let hz = |f: f64| <T as NumCast>::from(f).unwrap();
+
+        let (nz, poles) = match weighting
+        {
+            FrequencyWeighting::A => (4, vec![F1, F1, F2, F3, F4, F4]),
+            FrequencyWeighting::C => (2, vec![F1, F1, F4, F4]),
+            FrequencyWeighting::Z => (0, vec![])
+        };
+
+        let (z, p, s_ref): (Vec<Complex<T>>, Vec<Complex<T>>, Complex<T>) = match plane
+        {
+            FilterGenPlane::S => (
+                vec![Complex::from(zero); nz],
+                poles.iter()
+                    .map(|&f| Complex::from(-T::TAU()*hz(f)))
+                    .collect(),
+                Complex::new(zero, T::TAU()*hz(F_REF))
+            ),
+            FilterGenPlane::Z { sampling_frequency } => {
+                let fs = match sampling_frequency
+                {
+                    Some(fs) if fs > zero && fs.is_finite() => fs,
+                    _ => return Err(FilterGenError::FrequenciesOutOfRange)
+                };
+                if !(hz(F_REF) < fs/two)
+                {
+                    return Err(FilterGenError::FrequenciesOutOfRange)
+                }
+                let eighth = fs/(two*two*two);
+                (
+                    vec![Complex::from(one); nz].into_iter()
+                        .chain(core::iter::repeat(Complex::from(zero)).take(poles.len() - nz))
+                        .collect(),
+                    poles.iter()
+                        .map(|&f| {
+                            let f = hz(f);
+                            if f > eighth
+                            {
+                                Complex::from((-T::TAU()*f/fs).exp())
+                            }
+                            else
+                            {
+                                let w = two*fs*(T::PI()*f/fs).tan();
+                                Complex::from((two*fs - w)/(two*fs + w))
+                            }
+                        }).collect(),
+                    Complex::cis(T::TAU()*hz(F_REF)/fs)
+                )
+            }
+        };
+
+        let h_ref = z.iter()
+            .map(|&z| s_ref - z)
+            .product::<Complex<T>>()
+            /p.iter()
+                .map(|&p| s_ref - p)
+                .product::<Complex<T>>();
+
+        Ok(Zpk::new(z, p, h_ref.norm().recip()))
+    }
+}
+
+impl<T> WeightingFilter for Tf<T, Vec<T>, Vec<T>>
+where
+    T: Float + FloatConst,
+    Zpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T>: WeightingFilter + ToTf<T, Vec<T>, Vec<T>, (), ()> + System<Set = T>
+{
+    fn weighting_filter(weighting: FrequencyWeighting, plane: FilterGenPlane<T>) -> Result<Self, FilterGenError>
+    {
+        let zpk = Zpk::weighting_filter(weighting, plane)?;
+
+        Ok(zpk.to_tf((), ()))
+    }
+}
+
+impl<T> WeightingFilter for Sos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>>
+where
+    T: Float + FloatConst,
+    Zpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T>: WeightingFilter + ToSos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>, (), ()> + System<Set = T>
+{
+    fn weighting_filter(weighting: FrequencyWeighting, plane: FilterGenPlane<T>) -> Result<Self, FilterGenError>
+    {
+        let zpk = Zpk::weighting_filter(weighting, plane)?;
+
+        Ok(zpk.to_sos((), ()))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use num::Complex;
+
+    use crate::{gen::filter::{FilterGenPlane, FrequencyWeighting, WeightingFilter}, analysis::FreqS, systems::{Sos, Tf, Zpk}};
+
+    #[test]
+    fn test()
+    {
+        let h: Zpk<Complex<f64>, Vec<_>, Vec<_>, f64> = Zpk::weighting_filter(FrequencyWeighting::A, FilterGenPlane::S)
+            .unwrap();
+
+        // Nominal A-weighting at 100 Hz and 10 kHz, from IEC 61672-1 table 3.
+        let [h_100, h_10k] = h.freqs([Complex::new(0.0, std::f64::consts::TAU*100.0), Complex::new(0.0, std::f64::consts::TAU*10000.0)]);
+        assert!((20.0*h_100.norm().log10() + 19.1).abs() < 0.1);
+        assert!((20.0*h_10k.norm().log10() + 2.5).abs() < 0.1);
+
+        // The digital filters at 48 kHz, against IEC 61672-1 table 3 with the class 1 tolerances.
+        const FS: f64 = 48000.0;
+        for (weighting, nominal) in [
+            (FrequencyWeighting::A, [-19.1, 0.0, -2.5]),
+            (FrequencyWeighting::C, [-0.3, 0.0, -4.4])
+        ]
+        {
+            let h: Sos<f64, [_; 3], [_; 3], Vec<Tf<f64, [_; 3], [_; 3]>>> = Sos::weighting_filter(
+                weighting,
+                FilterGenPlane::Z { sampling_frequency: Some(FS) }
+            ).unwrap();
+
+            for ((f, nominal), (lower, upper)) in [100.0, 1000.0, 10000.0].into_iter()
+                .zip(nominal)
+                .zip([(-1.0, 1.0), (-0.7, 0.7), (-3.0, 2.0)])
+            {
+                let z = Complex::cis(-std::f64::consts::TAU*f/FS);
+                let h_f: Complex<f64> = h.sos.iter()
+                    .map(|h| {
+                        let b: Complex<f64> = h.b.iter()
+                            .rev()
+                            .fold(Complex::new(0.0, 0.0), |y, &b| y*z + b);
+                        let a: Complex<f64> = h.a.iter()
+                            .rev()
+                            .fold(Complex::new(0.0, 0.0), |y, &a| y*z + a);
+                        b/a
+                    }).product();
+                let e = 20.0*h_f.norm().log10() - nominal;
+                assert!(e >= lower && e <= upper, "{:?}-weighting off by {} dB at {} Hz", weighting, e, f);
+            }
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use num::Float;
+use option_trait::Maybe;
+use thiserror::Error;
+
+use crate::{analysis::{XCorr, XCorrScale}, identification::ar::Levinson, quantities::{List, Lists}, System, systems::{Ar, Tf}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum WienerError
+{
+    #[error("Observed signal and desired signal must have equal length.")]
+    SignalsDifferentLength,
+    #[error("Autocorrelation and crosscorrelation must have equal, non-zero length.")]
+    CorrelationsDifferentLength,
+    #[error("Autocorrelation must be positive definite.")]
+    NotPositiveDefinite,
+    #[error("Power spectral density and cross power spectral density must have equal length.")]
+    SpectraDifferentLength
+}
+
+/// FIR Wiener filter, i.e. the minimum mean-square error estimator of the desired signal from the observed signal.
+///
+/// The correlations are estimated with [XCorr], and the normal equations are solved with [Levinson].
+pub trait Wiener<O, X, D>: System + Sized
+where
+    Self::Set: Float,
+    O: Maybe<usize>,
+    X: List<Self::Set>,
+    D: List<Self::Set>
+{
+    fn wiener(order: O, observed: X, desired: D) -> Result<Self, WienerError>;
+}
+
+/// FIR Wiener filter from the autocorrelation of the observed signal and its crosscorrelation with the desired signal, both at
+/// lags 0 up to and including the filter order.
+///
+/// The crosscorrelation at lag k is E[d(n)x(n - k)].
+pub trait WienerCorr<R, P>: System + Sized
+where
+    Self::Set: Float,
+    R: List<Self::Set>,
+    P: List<Self::Set>
+{
+    fn wiener_corr(autocorrelation: R, crosscorrelation: P) -> Result<Self, WienerError>;
+}
+
+impl<T, R, P> WienerCorr<R, P> for Tf<T, Vec<T>, ()>
+where
+    T: Float,
+    R: List<T>,
+    P: List<T>,
+    Ar<T, Vec<T>, (Vec<T>, T)>: Levinson<Vec<T>, usize, Vec<T>>,
+    Self: System<Set = T>
+{
+    fn wiener_corr(autocorrelation: R, crosscorrelation: P) -> Result<Self, WienerError>
+    {
+        let zero = T::zero();
+        let one = T::one();
+
+        let r = autocorrelation.as_view_slice();
+        let p = crosscorrelation.as_view_slice();
+        let m = p.len();
+        if m == 0 || r.len() != m
+        {
+            return Err(WienerError::CorrelationsDifferentLength)
+        }
+        if !(r[0] > zero)
+        {
+            return Err(WienerError::NotPositiveDefinite)
+        }
+
+        // Reflection coefficients of the Toeplitz system, from which the backward predictors of every order are built.
+        let k = if m > 1
+        {
+            let (_, k): (Ar<T, Vec<T>, (Vec<T>, T)>, Vec<T>) = Ar::levinson(r.to_vec(), m - 1);
+            k
+        }
+        else
+        {
+            vec![]
+        };
+
+        let mut a = vec![one];
+        let mut e = r[0];
+        let mut w = vec![p[0]/r[0]];
+        for (i, &k) in k.iter()
+            .enumerate()
+        {
+            let order = i + 1;
+            a = (0..=order).map(|j| {
+                    let aj = a.get(j).copied().unwrap_or(zero);
+                    let ar = if j == 0 {zero} else {a.get(order - j).copied().unwrap_or(zero)};
+                    aj + k*ar
+                }).collect();
+            e = e*(one - k*k);
+            if !(e > zero) || !e.is_finite()
+            {
+                return Err(WienerError::NotPositiveDefinite)
+            }
+
+            let gamma = p[order] - w.iter()
+                .enumerate()
+                .map(|(j, &w)| r[order - j]*w)
+                .fold(zero, |s, x| s + x);
+            w.push(zero);
+            for (w, &a) in w.iter_mut()
+                .zip(a.iter().rev())
+            {
+                *w = *w + gamma/e*a
+            }
+        }
+
+        Ok(Tf::new(w, ()))
+    }
+}
+
+impl<T, X, D> Wiener<usize, X, D> for Tf<T, Vec<T>, ()>
+where
+    T: Float,
+    X: List<T>,
+    D: List<T>,
+    Vec<T>: XCorr<T, T, (), T> + XCorr<T, T, Vec<T>, T> + Lists<T, RowsMapped<Vec<T>> = Vec<T>>,
+    Self: WienerCorr<Vec<T>, Vec<T>> + System<Set = T>
+{
+    fn wiener(order: usize, observed: X, desired: D) -> Result<Self, WienerError>
+    {
+        let x = observed.as_view_slice();
+        let d = desired.as_view_slice();
+        if x.len() != d.len()
+        {
+            return Err(WienerError::SignalsDifferentLength)
+        }
+        let n = order + 1;
+
+        // Keeps lags 0 to n - 1 of the 2n + 1 lags returned.
+        let lags = |c: Vec<T>| c.into_iter()
+            .skip(n)
+            .take(n)
+            .collect::<Vec<_>>();
+        let (r, _): (Vec<T>, _) = x.to_vec()
+            .xcorr((), XCorrScale::Biased, n);
+        let (p, _): (Vec<T>, _) = d.to_vec()
+            .xcorr(x.to_vec(), XCorrScale::Biased, n);
+
+        Self::wiener_corr(lags(r), lags(p))
+    }
+}
+
+impl<T, X, D, const N: usize> Wiener<(), X, D> for Tf<T, [T; N], ()>
+where
+    T: Float,
+    X: List<T>,
+    D: List<T>,
+    Tf<T, Vec<T>, ()>: Wiener<usize, X, D> + System<Set = T>,
+    Self: System<Set = T>,
+    [(); N - 1]:
+{
+    fn wiener((): (), observed: X, desired: D) -> Result<Self, WienerError>
+    {
+        let h = Tf::wiener(N - 1, observed, desired)?;
+
+        Ok(Tf::new(h.b.into_inner().try_into().ok().unwrap(), ()))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use rand::distributions::uniform::SampleRange;
+
+    use crate::{gen::filter::Wiener, systems::Tf};
+
+    #[test]
+    fn test()
+    {
+        let mut rng = rand::thread_rng();
+
+        // Equalization of a known FIR channel, seen from the other side: the estimate of d from x is the channel itself.
+        let h = [1.0, 0.5, -0.25];
+        let x: Vec<f64> = (0..8192).map(|_| (-1.0..1.0).sample_single(&mut rng))
+            .collect();
+        let d: Vec<f64> = (0..x.len()).map(|n| h.iter()
+                .enumerate()
+                .filter(|&(k, _)| k <= n)
+                .map(|(k, &h)| h*x[n - k])
+                .sum()
+            ).collect();
+
+        let w: Tf<f64, [_; 5], ()> = Tf::wiener((), x, d)
+            .unwrap();
+
+        for (w, h) in w.b.into_inner()
+            .into_iter()
+            .zip(h.into_iter().chain([0.0, 0.0]))
+        {
+            assert!((w - h).abs() < 0.05, "{} != {}", w, h)
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use num::{Complex, Float};
+
+use crate::{gen::filter::WienerError, quantities::List};
+
+/// Non-causal Wiener filter, H(f) = Pxd(f)/(Pxx(f) + ε), from a cross power spectral density estimate and the power
+/// spectral density estimate of the observed signal.
+///
+/// With x the observed signal and d the desired signal, the spectra are typically estimated as `x.cpsd(d, ...)` and
+/// `x.psd(...)`, in which case this is the same estimator as [TfEstimate](crate::analysis::TfEstimate), but with a
+/// regularization term for frequencies where the observed signal has no power.
+pub trait WienerPsd<T, P>: List<Complex<T>>
+where
+    T: Float,
+    P: List<T>
+{
+    fn wiener_psd(self, psd: P, regularization: T) -> Result<Vec<Complex<T>>, WienerError>;
+}
+
+impl<T, L, P> WienerPsd<T, P> for L
+where
+    T: Float,
+    L: List<Complex<T>>,
+    P: List<T>
+{
+    fn wiener_psd(self, psd: P, regularization: T) -> Result<Vec<Complex<T>>, WienerError>
+    {
+        let cross = self.as_view_slice();
+        let psd = psd.as_view_slice();
+        if cross.len() != psd.len()
+        {
+            return Err(WienerError::SpectraDifferentLength)
+        }
+
+        Ok(cross.iter()
+            .zip(psd.iter())
+            .map(|(&c, &p)| {
+                let p = p + regularization;
+                if p > T::zero() {c/p} else {Complex::new(T::zero(), T::zero())}
+            }).collect())
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use num::Complex;
+
+    use crate::gen::filter::WienerPsd;
+
+    #[test]
+    fn test()
+    {
+        let h = [Complex::new(2.0, 0.0), Complex::new(0.0, 1.0), Complex::new(1.0, 0.0)]
+            .wiener_psd([1.0, 2.0, 0.0], 0.0)
+            .unwrap();
+
+        assert_eq!(h, vec![Complex::new(2.0, 0.0), Complex::new(0.0, 0.5), Complex::new(0.0, 0.0)]);
+    }
+}
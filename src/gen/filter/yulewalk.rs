@@ -0,0 +1,341 @@
+use core::{iter::Sum, ops::{AddAssign, MulAssign}};
+
+use array_math::SliceMath;
+use ndarray::{Array1, Array2};
+use ndarray_linalg::{least_squares::LeastSquaresSvd, Lapack, Solve};
+use num::{traits::FloatConst, Complex, Float, NumCast, Zero};
+use option_trait::Maybe;
+use thiserror::Error;
+
+use crate::{quantities::List, Plane, System, systems::Tf, transforms::filter::Stabilize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum YuleWalkError
+{
+    #[error("Filter order must be at least 1.")]
+    ZeroOrder,
+    #[error("List of frequencies and list of magnitudes must have equal length.")]
+    FrequenciesAndMagnitudesDifferentLength,
+    #[error("Frequencies must be monotonic starting at zero.")]
+    FrequenciesNotNondecreasing,
+    #[error("Frequencies must start at zero and end at 1/2 the sampling frequency, or if no sampling frequency is specified, end at 1.")]
+    FrequenciesOutOfRange,
+    #[error("Magnitudes must be non-negative.")]
+    NegativeMagnitude,
+    #[error("Sampling frequency must be a positive number.")]
+    InvalidSamplingFrequency,
+    #[error("Numerical error.")]
+    NumericalError
+}
+
+pub trait YuleWalk<O, F, M>: System + Sized
+where
+    Self::Set: Float,
+    O: Maybe<usize>,
+    F: List<Self::Set>,
+    M: List<Self::Set, Length = F::Length>
+{
+    fn yulewalk<FS>(
+        order: O,
+        frequencies: F,
+        magnitudes: M,
+        sampling_frequency: FS
+    ) -> Result<Self, YuleWalkError>
+    where
+        FS: Maybe<Self::Set>;
+}
+
+impl<T, F, M> YuleWalk<usize, F, M> for Tf<T, Vec<T>, Vec<T>>
+where
+    T: Float + FloatConst + Lapack<Real = T, Complex = Complex<T>>,
+    Complex<T>: MulAssign + AddAssign + MulAssign<T> + Sum,
+    F: List<T>,
+    M: List<T, Length = F::Length>,
+    Self: Stabilize<Output = Self> + System<Set = T>
+{
+    fn yulewalk<FS>(
+        order: usize,
+        frequencies: F,
+        magnitudes: M,
+        sampling_frequency: FS
+    ) -> Result<Self, YuleWalkError>
+    where
+        FS: Maybe<T>
+    {
+        let zero = T::zero();
+        let one = T::one();
+        let two = one + one;
+
+        if order < 1
+        {
+            return Err(YuleWalkError::ZeroOrder)
+        }
+        let (f, m) = magnitude_points(frequencies.as_view_slice(), magnitudes.as_view_slice(), sampling_frequency)?;
+
+        let npt = 512.max((4*order).next_power_of_two());
+        let nfft = 2*npt;
+        let ht = magnitude_grid(&f, &m, npt + 1);
+
+        // Autocorrelation of the desired power spectrum.
+        let mut r: Vec<Complex<T>> = (0..nfft).map(|k| {
+                let h = ht[if k <= npt {k} else {nfft - k}];
+                Complex::from(h*h)
+            }).collect();
+        r.ifft();
+        let nr = (4*order).max(2*order + 2).min(npt);
+        let nrf = <T as NumCast>::from(nr - 1).unwrap();
+        let r: Vec<T> = r[..nr].iter()
+            .enumerate()
+            .map(|(k, r)| {
+                let w = <T as NumCast>::from(0.54).unwrap()
+                    + <T as NumCast>::from(0.46).unwrap()*Float::cos(T::PI()*<T as NumCast>::from(k).unwrap()/nrf);
+                r.re*w
+            }).collect();
+
+        // Modified Yule-Walker equations for the denominator.
+        let rm = Array2::from_shape_fn((order, order), |(i, j)| r[order + i - j]);
+        let rhs = Array1::from_shape_fn(order, |i| -r[order + 1 + i]);
+        let a = rm.solve(&rhs)
+            .map_err(|_| YuleWalkError::NumericalError)?;
+        let a: Vec<T> = core::iter::once(one)
+            .chain(a.into_iter())
+            .collect();
+        let a = Tf::new(vec![one], a)
+            .stabilize(Plane::Z)
+            .a
+            .into_inner();
+
+        // Additive decomposition of the spectrum, followed by its minimum-phase spectral factor.
+        let h: Vec<T> = core::iter::once(r[0]/two)
+            .chain(r[1..].iter().copied())
+            .collect();
+        let q = numerator_fit(&h, &a, order)?;
+        let mut qf: Vec<Complex<T>> = q.iter()
+            .map(|&q| Complex::from(q))
+            .chain(core::iter::repeat(Complex::zero()))
+            .take(nfft)
+            .collect();
+        let mut af: Vec<Complex<T>> = a.iter()
+            .map(|&a| Complex::from(a))
+            .chain(core::iter::repeat(Complex::zero()))
+            .take(nfft)
+            .collect();
+        qf.fft();
+        af.fft();
+        let floor = Float::sqrt(T::epsilon());
+        let log_mag: Vec<T> = qf.into_iter()
+            .zip(af)
+            .map(|(q, a)| Float::ln(Float::max(two*(q/a).re, floor))/two)
+            .collect();
+        let mut hh = minimum_phase_spectrum(&log_mag);
+        hh.ifft();
+        let hh: Vec<T> = hh[..nr].iter()
+            .map(|h| h.re)
+            .collect();
+
+        let b = numerator_fit(&hh, &a, order)?;
+
+        Ok(Tf::new(b, a))
+    }
+}
+
+impl<T, F, M, const N: usize> YuleWalk<(), F, M> for Tf<T, [T; N], [T; N]>
+where
+    T: Float,
+    F: List<T>,
+    M: List<T, Length = F::Length>,
+    Tf<T, Vec<T>, Vec<T>>: YuleWalk<usize, F, M> + System<Set = T>,
+    [(); N - 2]:
+{
+    fn yulewalk<FS>(
+        (): (),
+        frequencies: F,
+        magnitudes: M,
+        sampling_frequency: FS
+    ) -> Result<Self, YuleWalkError>
+    where
+        FS: Maybe<T>
+    {
+        let h = Tf::yulewalk(N - 1, frequencies, magnitudes, sampling_frequency)?;
+
+        Ok(Tf::new(
+            h.b.into_inner()
+                .try_into()
+                .ok()
+                .unwrap(),
+            h.a.into_inner()
+                .try_into()
+                .ok()
+                .unwrap()
+        ))
+    }
+}
+
+pub(crate) fn magnitude_points<T, FS>(f: &[T], m: &[T], sampling_frequency: FS) -> Result<(Vec<T>, Vec<T>), YuleWalkError>
+where
+    T: Float,
+    FS: Maybe<T>
+{
+    let zero = T::zero();
+    let one = T::one();
+    let two = one + one;
+
+    if f.len() != m.len()
+    {
+        return Err(YuleWalkError::FrequenciesAndMagnitudesDifferentLength)
+    }
+    let mut f = f.to_vec();
+    if let Some(fs) = sampling_frequency.into_option()
+    {
+        if !(fs > zero) || !fs.is_finite()
+        {
+            return Err(YuleWalkError::InvalidSamplingFrequency)
+        }
+        for f in f.iter_mut()
+        {
+            *f = *f*two/fs
+        }
+    }
+    if !f.is_sorted()
+    {
+        return Err(YuleWalkError::FrequenciesNotNondecreasing)
+    }
+    if f.len() < 2 || f[0] != zero || f[f.len() - 1] != one
+    {
+        return Err(YuleWalkError::FrequenciesOutOfRange)
+    }
+    if m.iter()
+        .any(|&m| !(m >= zero))
+    {
+        return Err(YuleWalkError::NegativeMagnitude)
+    }
+
+    Ok((f, m.to_vec()))
+}
+
+/// Piecewise-linear interpolation of the frequency/magnitude points onto `n` evenly spaced frequencies from 0 to 1.
+///
+/// A repeated frequency marks a step, which is sampled at the midpoint of its two magnitudes.
+pub(crate) fn magnitude_grid<T>(f: &[T], m: &[T], n: usize) -> Vec<T>
+where
+    T: Float
+{
+    let two = T::one() + T::one();
+    let nf = <T as NumCast>::from(n - 1).unwrap();
+    (0..n).map(|k| {
+            let x = <T as NumCast>::from(k).unwrap()/nf;
+            let i = f.iter()
+                .take_while(|&&f| f <= x)
+                .count()
+                .max(1)
+                .min(f.len() - 1);
+            if f[i - 1] == x && i >= 2 && f[i - 2] == x
+            {
+                return (m[i - 2] + m[i - 1])/two
+            }
+            if f[i] > f[i - 1]
+            {
+                m[i - 1] + (m[i] - m[i - 1])*(x - f[i - 1])/(f[i] - f[i - 1])
+            }
+            else
+            {
+                m[i]
+            }
+        }).collect()
+}
+
+/// Spectrum of the minimum-phase sequence whose log-magnitude over the whole unit circle is `log_mag`.
+pub(crate) fn minimum_phase_spectrum<T>(log_mag: &[T]) -> Vec<Complex<T>>
+where
+    T: Float,
+    Complex<T>: MulAssign + AddAssign + MulAssign<T> + Sum
+{
+    let two = T::one() + T::one();
+    let n = log_mag.len();
+
+    let mut c: Vec<Complex<T>> = log_mag.iter()
+        .map(|&l| Complex::from(l))
+        .collect();
+    c.ifft();
+    for (k, c) in c.iter_mut()
+        .enumerate()
+    {
+        if k > 0 && k < n/2
+        {
+            *c = Complex::from(c.re*two)
+        }
+        else if k > n/2
+        {
+            *c = Complex::zero()
+        }
+        else
+        {
+            *c = Complex::from(c.re)
+        }
+    }
+    c.fft();
+    for c in c.iter_mut()
+    {
+        *c = c.exp()
+    }
+    c
+}
+
+/// Least-squares numerator `b` such that `b/a` has impulse response `h`.
+fn numerator_fit<T>(h: &[T], a: &[T], nb: usize) -> Result<Vec<T>, YuleWalkError>
+where
+    T: Float + Lapack<Real = T>
+{
+    let n = h.len();
+    let mut impr = vec![T::zero(); n];
+    for k in 0..n
+    {
+        let x = if k == 0 {T::one()} else {T::zero()};
+        let y = a[1..].iter()
+            .zip(impr[..k].iter().rev())
+            .fold(x, |y, (&a, &h)| y - a*h);
+        impr[k] = y/a[0];
+    }
+    let t = Array2::from_shape_fn((n, nb + 1), |(i, j)| if i >= j {impr[i - j]} else {T::zero()});
+    let b = t.least_squares(&Array1::from_vec(h.to_vec()))
+        .map_err(|_| YuleWalkError::NumericalError)?;
+
+    Ok(b.solution.to_vec())
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::PI;
+
+    use array_math::ArrayOps;
+
+    use crate::{plot, gen::filter::YuleWalk, Plane, analysis::{IsStable, RealFreqZ}, systems::Tf};
+
+    #[test]
+    fn test()
+    {
+        let h: Tf<f64, Vec<_>, Vec<_>> = Tf::yulewalk(
+            8,
+            [0.0, 0.6, 0.6, 1.0],
+            [1.0, 1.0, 0.0, 0.0],
+            ()
+        ).unwrap();
+
+        assert!(h.is_stable((), Plane::Z));
+
+        const N: usize = 1024;
+        let (h_f, w): ([_; N], _) = h.real_freqz(());
+
+        // The magnitude follows the target within the passband, and within the stopband away from the step.
+        for (x, target, tolerance) in [(0.1, 1.0, 0.05), (0.3, 1.0, 0.05), (0.5, 1.0, 0.05), (0.8, 0.0, 0.1), (0.9, 0.0, 0.1)]
+        {
+            let i = (x*N as f64).round() as usize;
+            assert!((w[i]/PI - x).abs() < 1e-3);
+            assert!((h_f[i].norm() - target).abs() < tolerance);
+        }
+
+        plot::plot_curves("H(e^jw)", "plots/h_z_yulewalk.png", [&w.zip(h_f.map(|h| h.norm()))])
+            .unwrap();
+    }
+}
@@ -0,0 +1,130 @@
+use core::ops::Mul;
+
+use ndarray::{Array1, Array2};
+use ndarray_linalg::Lapack;
+use num::{complex::ComplexFloat, Float, NumCast, One, Zero};
+
+use crate::{quantities::{ListOrSingle, Lists, OwnedList}, util};
+
+/// Trend fitted to, and removed from, a signal.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum DetrendType
+{
+    /// The mean.
+    Constant,
+    /// The least-squares straight line.
+    #[default]
+    Linear,
+    /// The least-squares polynomial of the given order.
+    Polynomial(usize),
+    /// The least-squares continuous piecewise-linear function, with breakpoints at the given sample indices.
+    PiecewiseLinear(Vec<usize>)
+}
+
+pub trait Detrend<T>: Lists<T>
+where
+    T: ComplexFloat
+{
+    /// Removes a trend from the signal.
+    ///
+    /// Returns the detrended signal and the fitted trend, which sum to the original signal.
+    fn detrend(self, detrend_type: DetrendType) -> Self::RowsMapped<(Self::RowOwned, Self::RowOwned)>;
+}
+
+impl<T, L> Detrend<T> for L
+where
+    T: ComplexFloat + Lapack<Real = <T as ComplexFloat>::Real> + Mul<<T as ComplexFloat>::Real, Output = T>,
+    <T as ComplexFloat>::Real: Into<T>,
+    L: Lists<T, RowOwned: OwnedList<T> + Clone>
+{
+    fn detrend(self, detrend_type: DetrendType) -> Self::RowsMapped<(Self::RowOwned, Self::RowOwned)>
+    {
+        self.map_rows_into_owned(|mut x| {
+            let n = x.as_mut_slice().len();
+            let mut trend = x.clone();
+            if n == 0
+            {
+                return (x, trend)
+            }
+
+            // Time is scaled to [-1, 1] for the conditioning of the polynomial basis.
+            let one = <T as ComplexFloat>::Real::one();
+            let nm1 = <<T as ComplexFloat>::Real as NumCast>::from(n.saturating_sub(1).max(1)).unwrap();
+            let time = |i: usize| (<<T as ComplexFloat>::Real as NumCast>::from(2*i).unwrap() - nm1)/nm1;
+
+            let columns: Vec<Vec<<T as ComplexFloat>::Real>> = match &detrend_type
+            {
+                DetrendType::Constant => vec![vec![one; n]],
+                DetrendType::Linear => vec![vec![one; n], (0..n).map(time).collect()],
+                DetrendType::Polynomial(order) => (0..=*order).map(|k| (0..n).map(|i| Float::powi(time(i), k as i32))
+                        .collect()
+                    ).collect(),
+                DetrendType::PiecewiseLinear(breakpoints) => {
+                    let mut breakpoints: Vec<usize> = breakpoints.iter()
+                        .copied()
+                        .filter(|&b| b > 0 && b + 1 < n)
+                        .collect();
+                    breakpoints.sort_unstable();
+                    breakpoints.dedup();
+                    [vec![one; n], (0..n).map(time).collect()].into_iter()
+                        .chain(breakpoints.into_iter()
+                            .map(|b| (0..n).map(|i| Float::max(time(i) - time(b), <T as ComplexFloat>::Real::zero()))
+                                .collect()
+                            )
+                        ).collect()
+                }
+            };
+
+            let a = Array2::from_shape_fn((n, columns.len()), |(i, k)| columns[k][i].into());
+            let coeffs = util::pinv(a.clone())
+                .dot(&Array1::from_iter(x.as_mut_slice().iter().copied()));
+            let fit = a.dot(&coeffs);
+
+            for ((x, t), f) in x.as_mut_slice()
+                .iter_mut()
+                .zip(trend.as_mut_slice()
+                    .iter_mut()
+                )
+                .zip(fit.iter())
+            {
+                *t = *f;
+                *x = *x - *f
+            }
+            (x, trend)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use crate::operations::{Detrend, DetrendType};
+
+    #[test]
+    fn test()
+    {
+        const N: usize = 100;
+
+        // A broken line, with a kink at sample 40, plus a small oscillation.
+        let line = |i: usize| if i < 40 {1.0 + 0.1*i as f64} else {5.0 - 0.05*(i - 40) as f64};
+        let x: Vec<f64> = (0..N).map(|i| line(i) + 1e-3*(i as f64).sin())
+            .collect();
+
+        let (y, trend) = x.clone()
+            .detrend(DetrendType::PiecewiseLinear(vec![40]));
+        for (i, (y, t)) in y.into_iter()
+            .zip(trend)
+            .enumerate()
+        {
+            assert!((t - line(i)).abs() < 1e-2);
+            assert!((y + t - x[i]).abs() < 1e-12);
+        }
+
+        // Lower order trends leave a residual, but the mean is always removed.
+        let (y, _) = x.clone()
+            .detrend(DetrendType::Constant);
+        assert!(y.into_iter().sum::<f64>().abs() < 1e-9);
+        let (y, _) = x.detrend(DetrendType::Polynomial(3));
+        assert!(y.iter().all(|y| y.abs() < 1.0));
+    }
+}
@@ -0,0 +1,434 @@
+use ndarray::{Array1, Array2};
+use ndarray_linalg::{Lapack, Solve};
+use num::{Float, NumCast};
+use thiserror::Error;
+
+use crate::systems::Tf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum AdaptiveFilterError
+{
+    #[error("Step size must be a positive number.")]
+    InvalidStepSize,
+    #[error("Leakage must be non-negative, and less than the reciprocal of the step size.")]
+    InvalidLeakage,
+    #[error("Forgetting factor must be in the interval (0, 1].")]
+    InvalidForgettingFactor,
+    #[error("Initial inverse covariance must be a positive number.")]
+    InvalidInitialInverseCovariance,
+    #[error("Regularization must be a non-negative number.")]
+    InvalidRegularization,
+    #[error("Projection order must be at least 1.")]
+    ZeroProjectionOrder,
+    #[error("Input signal and desired signal must have equal length.")]
+    SignalsDifferentLength
+}
+
+/// Update rule of an [AdaptiveFilter].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdaptiveAlgorithm<T>
+where
+    T: Float
+{
+    /// Least mean squares, w += μeu.
+    Lms {
+        step_size: T
+    },
+    /// Normalized least mean squares, w += μeu/(ε + |u|²).
+    Nlms {
+        step_size: T,
+        regularization: T
+    },
+    /// Leaky least mean squares, w = (1 - μγ)w + μeu.
+    LeakyLms {
+        step_size: T,
+        leakage: T
+    },
+    /// Sign-error least mean squares, w += μsgn(e)u.
+    SignErrorLms {
+        step_size: T
+    },
+    /// Sign-data least mean squares, w += μesgn(u).
+    SignDataLms {
+        step_size: T
+    },
+    /// Sign-sign least mean squares, w += μsgn(e)sgn(u).
+    SignSignLms {
+        step_size: T
+    },
+    /// Recursive least squares with exponential forgetting. The inverse covariance starts out as a scaled identity matrix.
+    Rls {
+        forgetting_factor: T,
+        initial_inverse_covariance: T
+    },
+    /// Affine projection, using the latest `projection_order` input vectors.
+    Apa {
+        step_size: T,
+        projection_order: usize,
+        regularization: T
+    }
+}
+
+/// Adaptive FIR filter.
+///
+/// Each sample of the input signal is filtered with the current weights, and the weights are then updated to reduce the error
+/// against the desired signal. The input history and adaptation state is kept between calls, so signals may be processed
+/// sample by sample or in arbitrary blocks.
+#[derive(Debug, Clone)]
+pub struct AdaptiveFilter<T>
+where
+    T: Float
+{
+    algorithm: AdaptiveAlgorithm<T>,
+    w: Vec<T>,
+    u: Vec<T>,
+    p: Array2<T>,
+    u_history: Vec<Vec<T>>,
+    d_history: Vec<T>
+}
+
+impl<T> AdaptiveFilter<T>
+where
+    T: Float + Lapack
+{
+    fn validate(algorithm: &AdaptiveAlgorithm<T>) -> Result<(), AdaptiveFilterError>
+    {
+        let zero = T::zero();
+        let one = T::one();
+
+        let valid = |x: T| x > zero && Float::is_finite(x);
+        let non_negative = |x: T| x >= zero && Float::is_finite(x);
+        match *algorithm
+        {
+            AdaptiveAlgorithm::Lms { step_size }
+            | AdaptiveAlgorithm::SignErrorLms { step_size }
+            | AdaptiveAlgorithm::SignDataLms { step_size }
+            | AdaptiveAlgorithm::SignSignLms { step_size } => if !valid(step_size)
+            {
+                return Err(AdaptiveFilterError::InvalidStepSize)
+            },
+            AdaptiveAlgorithm::Nlms { step_size, regularization } => {
+                if !valid(step_size)
+                {
+                    return Err(AdaptiveFilterError::InvalidStepSize)
+                }
+                if !non_negative(regularization)
+                {
+                    return Err(AdaptiveFilterError::InvalidRegularization)
+                }
+            },
+            AdaptiveAlgorithm::LeakyLms { step_size, leakage } => {
+                if !valid(step_size)
+                {
+                    return Err(AdaptiveFilterError::InvalidStepSize)
+                }
+                if !(leakage >= zero) || !(step_size*leakage < one)
+                {
+                    return Err(AdaptiveFilterError::InvalidLeakage)
+                }
+            },
+            AdaptiveAlgorithm::Rls { forgetting_factor, initial_inverse_covariance } => {
+                if !(forgetting_factor > zero) || !(forgetting_factor <= one)
+                {
+                    return Err(AdaptiveFilterError::InvalidForgettingFactor)
+                }
+                if !valid(initial_inverse_covariance)
+                {
+                    return Err(AdaptiveFilterError::InvalidInitialInverseCovariance)
+                }
+            },
+            AdaptiveAlgorithm::Apa { step_size, projection_order, regularization } => {
+                if !valid(step_size)
+                {
+                    return Err(AdaptiveFilterError::InvalidStepSize)
+                }
+                if projection_order < 1
+                {
+                    return Err(AdaptiveFilterError::ZeroProjectionOrder)
+                }
+                if !non_negative(regularization)
+                {
+                    return Err(AdaptiveFilterError::InvalidRegularization)
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates an adaptive filter of the given order, i.e. with `order + 1` weights, starting out at zero.
+    pub fn new(order: usize, algorithm: AdaptiveAlgorithm<T>) -> Result<Self, AdaptiveFilterError>
+    {
+        Self::validate(&algorithm)?;
+
+        let zero = T::zero();
+        let n = order + 1;
+        let mut filter = Self {
+            algorithm,
+            w: vec![zero; n],
+            u: vec![zero; n],
+            p: Array2::from_elem((0, 0), zero),
+            u_history: vec![],
+            d_history: vec![]
+        };
+        filter.reset();
+        Ok(filter)
+    }
+
+    pub fn algorithm(&self) -> AdaptiveAlgorithm<T>
+    {
+        self.algorithm
+    }
+
+    /// Switches to another update rule, keeping the current weights and input history.
+    ///
+    /// The adaptation state of the previous algorithm is discarded, and that of the new one starts out as in [new](AdaptiveFilter::new).
+    pub fn set_algorithm(&mut self, algorithm: AdaptiveAlgorithm<T>) -> Result<(), AdaptiveFilterError>
+    {
+        Self::validate(&algorithm)?;
+        self.algorithm = algorithm;
+        self.reset_state();
+        Ok(())
+    }
+
+    pub fn weights(&self) -> &[T]
+    {
+        &self.w
+    }
+
+    /// The current weights as an FIR filter.
+    pub fn tf(&self) -> Tf<T, Vec<T>, ()>
+    {
+        Tf::new(self.w.clone(), ())
+    }
+
+    /// Resets the weights to zero, and clears the input history and adaptation state.
+    pub fn reset(&mut self)
+    {
+        let zero = T::zero();
+
+        self.w.fill(zero);
+        self.u.fill(zero);
+        self.reset_state()
+    }
+
+    /// Clears the adaptation state of the algorithm.
+    fn reset_state(&mut self)
+    {
+        let zero = T::zero();
+        let n = self.w.len();
+
+        self.u_history.clear();
+        self.d_history.clear();
+        self.p = match self.algorithm
+        {
+            AdaptiveAlgorithm::Rls { forgetting_factor: _, initial_inverse_covariance } => Array2::from_diag_elem(n, initial_inverse_covariance),
+            _ => Array2::from_elem((0, 0), zero)
+        };
+    }
+
+    /// Filters one sample of the input signal and adapts to one sample of the desired signal.
+    ///
+    /// Returns the filter output and the error, both from before the update.
+    pub fn adapt(&mut self, x: T, d: T) -> (T, T)
+    {
+        let zero = T::zero();
+        let one = T::one();
+
+        self.u.rotate_right(1);
+        self.u[0] = x;
+
+        let dot = |a: &[T], b: &[T]| a.iter()
+            .zip(b.iter())
+            .fold(zero, |s, (&a, &b)| s + a*b);
+        let sign = |x: T| if x > zero {one} else if x < zero {-one} else {zero};
+
+        let y = dot(&self.w, &self.u);
+        let e = d - y;
+
+        match self.algorithm
+        {
+            AdaptiveAlgorithm::Lms { step_size } => for (w, &u) in self.w.iter_mut()
+                .zip(self.u.iter())
+            {
+                *w = *w + step_size*e*u
+            },
+            AdaptiveAlgorithm::Nlms { step_size, regularization } => {
+                let mu = step_size/(regularization + dot(&self.u, &self.u));
+                if Float::is_finite(mu)
+                {
+                    for (w, &u) in self.w.iter_mut()
+                        .zip(self.u.iter())
+                    {
+                        *w = *w + mu*e*u
+                    }
+                }
+            },
+            AdaptiveAlgorithm::LeakyLms { step_size, leakage } => for (w, &u) in self.w.iter_mut()
+                .zip(self.u.iter())
+            {
+                *w = (one - step_size*leakage)**w + step_size*e*u
+            },
+            AdaptiveAlgorithm::SignErrorLms { step_size } => for (w, &u) in self.w.iter_mut()
+                .zip(self.u.iter())
+            {
+                *w = *w + step_size*sign(e)*u
+            },
+            AdaptiveAlgorithm::SignDataLms { step_size } => for (w, &u) in self.w.iter_mut()
+                .zip(self.u.iter())
+            {
+                *w = *w + step_size*e*sign(u)
+            },
+            AdaptiveAlgorithm::SignSignLms { step_size } => for (w, &u) in self.w.iter_mut()
+                .zip(self.u.iter())
+            {
+                *w = *w + step_size*sign(e)*sign(u)
+            },
+            AdaptiveAlgorithm::Rls { forgetting_factor, initial_inverse_covariance: _ } => {
+                let u = Array1::from_vec(self.u.clone());
+                let pu = self.p.dot(&u);
+                let denominator = forgetting_factor + u.dot(&pu);
+                let k = pu.mapv(|pu| pu/denominator);
+                for (w, &k) in self.w.iter_mut()
+                    .zip(k.iter())
+                {
+                    *w = *w + k*e
+                }
+                // P is symmetric, so u'P = (Pu)'.
+                let n = self.w.len();
+                self.p = Array2::from_shape_fn((n, n), |(i, j)| (self.p[(i, j)] - k[i]*pu[j])/forgetting_factor);
+            },
+            AdaptiveAlgorithm::Apa { step_size, projection_order, regularization } => {
+                self.u_history.insert(0, self.u.clone());
+                self.u_history.truncate(projection_order);
+                self.d_history.insert(0, d);
+                self.d_history.truncate(projection_order);
+
+                let k = self.u_history.len();
+                let e: Array1<T> = self.u_history.iter()
+                    .zip(self.d_history.iter())
+                    .map(|(u, &d)| d - dot(&self.w, u))
+                    .collect();
+                let r = Array2::from_shape_fn((k, k), |(i, j)| {
+                    let r = dot(&self.u_history[i], &self.u_history[j]);
+                    if i == j {r + regularization} else {r}
+                });
+                if let Ok(g) = r.solve(&e)
+                {
+                    for (i, w) in self.w.iter_mut()
+                        .enumerate()
+                    {
+                        *w = *w + step_size*self.u_history.iter()
+                            .zip(g.iter())
+                            .fold(zero, |s, (u, &g)| s + u[i]*g)
+                    }
+                }
+            }
+        }
+
+        (y, e)
+    }
+
+    /// Filters a block of the input signal, adapting to the desired signal.
+    ///
+    /// Returns the filter output and the error for each sample.
+    pub fn adapt_block(&mut self, x: &[T], d: &[T]) -> Result<(Vec<T>, Vec<T>), AdaptiveFilterError>
+    {
+        if x.len() != d.len()
+        {
+            return Err(AdaptiveFilterError::SignalsDifferentLength)
+        }
+
+        Ok(x.iter()
+            .zip(d.iter())
+            .map(|(&x, &d)| self.adapt(x, d))
+            .unzip())
+    }
+
+    /// Filters a block of the input signal with the current weights, without adapting.
+    pub fn filter_block(&mut self, x: &[T]) -> Vec<T>
+    {
+        x.iter()
+            .map(|&x| {
+                self.u.rotate_right(1);
+                self.u[0] = x;
+                self.w.iter()
+                    .zip(self.u.iter())
+                    .fold(T::zero(), |s, (&w, &u)| s + w*u)
+            }).collect()
+    }
+
+    /// Mean squared error of a block of errors, in dB.
+    pub fn mse_db(e: &[T]) -> T
+    {
+        let n = <T as NumCast>::from(e.len().max(1)).unwrap();
+        <T as NumCast>::from(10.0).unwrap()*Float::log10(e.iter()
+            .fold(T::zero(), |s, &e| s + e*e)/n
+        )
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use rand::distributions::uniform::SampleRange;
+
+    use crate::operations::filtering::{AdaptiveAlgorithm, AdaptiveFilter, AdaptiveFilterError};
+
+    #[test]
+    fn test()
+    {
+        let mut rng = rand::thread_rng();
+
+        // System identification of an unknown FIR filter.
+        let h = [0.5, -0.3, 0.2, 0.1];
+        let x: Vec<f64> = (0..4000).map(|_| (-1.0..1.0).sample_single(&mut rng))
+            .collect();
+        let d: Vec<f64> = (0..x.len()).map(|n| h.iter()
+                .enumerate()
+                .filter(|&(k, _)| k <= n)
+                .map(|(k, &h)| h*x[n - k])
+                .sum()
+            ).collect();
+
+        for algorithm in [
+            AdaptiveAlgorithm::Lms { step_size: 0.05 },
+            AdaptiveAlgorithm::Nlms { step_size: 0.5, regularization: 1e-6 },
+            AdaptiveAlgorithm::LeakyLms { step_size: 0.05, leakage: 1e-6 },
+            AdaptiveAlgorithm::SignErrorLms { step_size: 0.002 },
+            AdaptiveAlgorithm::Rls { forgetting_factor: 0.999, initial_inverse_covariance: 100.0 },
+            AdaptiveAlgorithm::Apa { step_size: 0.5, projection_order: 3, regularization: 1e-6 }
+        ]
+        {
+            let mut filter = AdaptiveFilter::new(h.len() - 1, algorithm)
+                .unwrap();
+            filter.adapt_block(&x, &d)
+                .unwrap();
+
+            for (w, h) in filter.tf().b.into_inner().into_iter()
+                .zip(h)
+            {
+                assert!((w - h).abs() < 1e-2, "{:?}: {} != {}", algorithm, w, h)
+            }
+        }
+
+        // Switching algorithms mid-stream validates the new one, and sets up its state.
+        let mut filter = AdaptiveFilter::new(h.len() - 1, AdaptiveAlgorithm::Lms { step_size: 0.05 })
+            .unwrap();
+        filter.adapt_block(&x[..1000], &d[..1000])
+            .unwrap();
+        assert_eq!(
+            filter.set_algorithm(AdaptiveAlgorithm::Nlms { step_size: 0.5, regularization: -1.0 }),
+            Err(AdaptiveFilterError::InvalidRegularization)
+        );
+        filter.set_algorithm(AdaptiveAlgorithm::Rls { forgetting_factor: 0.999, initial_inverse_covariance: 100.0 })
+            .unwrap();
+        filter.adapt_block(&x[1000..], &d[1000..])
+            .unwrap();
+        for (&w, h) in filter.weights()
+            .iter()
+            .zip(h)
+        {
+            assert!((w - h).abs() < 1e-2)
+        }
+    }
+}
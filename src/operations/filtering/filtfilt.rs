@@ -1,8 +1,9 @@
 use core::{iter::Sum, ops::AddAssign};
 
 use num::complex::ComplexFloat;
+use option_trait::Maybe;
 
-use crate::{util::ComplexOp, operations::filtering::FilterMut, quantities::{ListOrSingle, Lists, MaybeList, MaybeLists, Polynomial}, systems::{Rtf, Tf}, RtfOrSystem, System};
+use crate::{util::ComplexOp, operations::filtering::{FiltFiltMethod, FiltFiltWith, FilterMut}, quantities::{ListOrSingle, Lists, MaybeList, MaybeLists, MaybeOwnedList, Polynomial}, systems::{Rtf, Sos, Ss, SsAMatrix, SsBMatrix, SsCMatrix, SsDMatrix, Tf, Zpk}, RtfOrSystem, System};
 
 pub trait FiltFilt<'a, X, XX>: System
 where
@@ -112,7 +113,7 @@ where
                 v.reverse();
                 
                 let mut rtf = Rtf::new(sys, Some(si.iter()
-                    .map(|&si| Into::<<T as ComplexOp<X>>::Output>::into(si)*v[v.len() - 1])
+                    .map(|&si| Into::<<T as ComplexOp<X>>::Output>::into(si)*v[0])
                     .collect()
                 ));
                 let mut v: Vec<_> = rtf.filter_mut(v);
@@ -128,6 +129,64 @@ where
     }
 }
 
+impl<'a, T, B, A, S, X, Y, XX> FiltFilt<'a, X, XX> for Sos<T, B, A, S>
+where
+    T: ComplexFloat + ComplexOp<X, Output = Y>,
+    B: Maybe<[T; 3]> + MaybeOwnedList<T>,
+    A: Maybe<[T; 3]> + MaybeOwnedList<T>,
+    S: MaybeList<Tf<T, B, A>>,
+    X: ComplexFloat<Real = T::Real> + Into<Y>,
+    Y: ComplexFloat<Real = T::Real>,
+    XX: Lists<X, Mapped<Y>: ListOrSingle<XX::Mapped<Y>>>,
+    Self: FiltFiltWith<X, XX>
+{
+    type Output = XX::Mapped<Y>;
+
+    fn filtfilt(&'a self, x: XX) -> Self::Output
+    {
+        self.filtfilt_with(x, FiltFiltMethod::default())
+    }
+}
+
+impl<'a, T, Z, P, K, X, Y, XX> FiltFilt<'a, X, XX> for Zpk<T, Z, P, K>
+where
+    T: ComplexFloat<Real = K::Real>,
+    K: ComplexFloat + ComplexOp<X, Output = Y>,
+    Z: MaybeList<T>,
+    P: MaybeList<T>,
+    X: ComplexFloat<Real = K::Real> + Into<Y>,
+    Y: ComplexFloat<Real = K::Real>,
+    XX: Lists<X, Mapped<Y>: ListOrSingle<XX::Mapped<Y>>>,
+    Self: FiltFiltWith<X, XX>
+{
+    type Output = XX::Mapped<Y>;
+
+    fn filtfilt(&'a self, x: XX) -> Self::Output
+    {
+        self.filtfilt_with(x, FiltFiltMethod::default())
+    }
+}
+
+impl<'a, T, A, B, C, D, X, Y, XX> FiltFilt<'a, X, XX> for Ss<T, A, B, C, D>
+where
+    T: ComplexFloat + ComplexOp<X, Output = Y>,
+    A: SsAMatrix<T, B, C, D>,
+    B: SsBMatrix<T, A, C, D>,
+    C: SsCMatrix<T, A, B, D>,
+    D: SsDMatrix<T, A, B, C>,
+    X: ComplexFloat<Real = T::Real> + Into<Y>,
+    Y: ComplexFloat<Real = T::Real>,
+    XX: Lists<X, Mapped<Y>: ListOrSingle<XX::Mapped<Y>>>,
+    Self: FiltFiltWith<X, XX>
+{
+    type Output = XX::Mapped<Y>;
+
+    fn filtfilt(&'a self, x: XX) -> Self::Output
+    {
+        self.filtfilt_with(x, FiltFiltMethod::default())
+    }
+}
+
 #[cfg(test)]
 mod test
 {
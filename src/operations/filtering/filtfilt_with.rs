@@ -0,0 +1,513 @@
+use core::ops::{Deref, Mul};
+
+use ndarray::{Array1, Array2};
+use ndarray_linalg::{Lapack, Solve};
+use num::{complex::ComplexFloat, One, Zero};
+use option_trait::Maybe;
+
+use crate::{analysis::FiltIc, quantities::{ContainerOrSingle, List, ListOrSingle, Lists, MaybeList, MaybeOwnedList}, systems::{Sos, Ss, SsAMatrix, SsBMatrix, SsCMatrix, SsDMatrix, Tf, Zpk}, transforms::system::ToSos, util::{self, ComplexOp}, System};
+
+/// Extension of the signal's edges before zero-phase filtering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FiltFiltPadding
+{
+    /// Point-reflection about the edge samples.
+    #[default]
+    Odd,
+    /// Mirror-reflection about the edge samples.
+    Even,
+    /// Repetition of the edge samples.
+    Constant,
+    None
+}
+
+/// How the transients at the edges are handled by zero-phase filtering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FiltFiltMethod
+{
+    /// Pads the signal, and starts both passes in the steady state of the first padded sample.
+    ///
+    /// The default padding length is three times the number of filter states plus one, limited to one less than the signal length.
+    Pad {
+        padding: FiltFiltPadding,
+        length: Option<usize>
+    },
+    /// Gustafsson's method, choosing the initial states of both passes so that filtering forward-backward gives the same result as
+    /// filtering backward-forward.
+    Gustafsson
+}
+
+impl Default for FiltFiltMethod
+{
+    fn default() -> Self
+    {
+        Self::Pad {
+            padding: FiltFiltPadding::default(),
+            length: None
+        }
+    }
+}
+
+pub trait FiltFiltWith<X, XX>: System
+where
+    Self::Set: ComplexOp<X>,
+    X: Into<<Self::Set as ComplexOp<X>>::Output> + ComplexFloat<Real = <Self::Set as ComplexFloat>::Real>,
+    XX: Lists<X>
+{
+    /// Zero-phase filtering of each row, without ever converting the system to a single transfer function.
+    fn filtfilt_with(&self, x: XX, method: FiltFiltMethod) -> XX::Mapped<<Self::Set as ComplexOp<X>>::Output>;
+}
+
+/// Realization of a SISO system that can be run from any initial state.
+enum Realization<Y>
+{
+    /// Transposed direct form II sections in cascade, as numerator and denominator normalized by the leading denominator coefficient.
+    Cascade(Vec<(Vec<Y>, Vec<Y>)>),
+    StateSpace {
+        a: Array2<Y>,
+        b: Array1<Y>,
+        c: Array1<Y>,
+        d: Y
+    }
+}
+
+impl<Y> Realization<Y>
+where
+    Y: ComplexFloat + Lapack<Real = <Y as ComplexFloat>::Real> + Mul<<Y as ComplexFloat>::Real, Output = Y>,
+    <Y as ComplexFloat>::Real: Into<Y>,
+    Tf<Y, Vec<Y>, Vec<Y>>: FiltIc<Y, Vec<Y>, Vec<Y>>
+{
+    fn cascade(sections: impl IntoIterator<Item = (Vec<Y>, Vec<Y>)>) -> Self
+    {
+        // Leading zeros are trimmed separately, as when filtering, and then both are padded to a common length.
+        let trim = |mut c: Vec<Y>| {
+            let k = c.iter()
+                .take_while(|c| c.is_zero())
+                .count();
+            c.drain(..k);
+            c
+        };
+        Realization::Cascade(sections.into_iter()
+            .map(|(b, a)| {
+                let mut b = trim(b);
+                let mut a = trim(a);
+                if a.is_empty()
+                {
+                    a.push(One::one())
+                }
+                let a0 = a[0];
+                let n = a.len().max(b.len()).max(1);
+                b.resize(n, Zero::zero());
+                a.resize(n, Zero::zero());
+                (
+                    b.into_iter().map(|b| b/a0).collect(),
+                    a.into_iter().map(|a| a/a0).collect()
+                )
+            }).collect()
+        )
+    }
+
+    fn order(&self) -> usize
+    {
+        match self
+        {
+            Realization::Cascade(sections) => sections.iter()
+                .map(|(b, _)| b.len() - 1)
+                .sum(),
+            Realization::StateSpace { a, .. } => a.dim().0
+        }
+    }
+
+    /// Filters the signal from the given initial state.
+    fn filter(&self, x: &[Y], zi: &[Y]) -> Vec<Y>
+    {
+        match self
+        {
+            Realization::Cascade(sections) => {
+                let mut y = x.to_vec();
+                let mut zi = zi.iter();
+                for (b, a) in sections.iter()
+                {
+                    let m = b.len() - 1;
+                    let mut z: Vec<Y> = (0..m).map(|_| zi.next().copied().unwrap_or_else(Zero::zero))
+                        .collect();
+                    for y in y.iter_mut()
+                    {
+                        let x = *y;
+                        *y = b[0]*x + z.first().copied().unwrap_or_else(Zero::zero);
+                        for i in 0..m
+                        {
+                            z[i] = b[i + 1]*x - a[i + 1]*(*y) + z.get(i + 1).copied().unwrap_or_else(Zero::zero)
+                        }
+                    }
+                }
+                y
+            },
+            Realization::StateSpace { a, b, c, d } => {
+                let mut s = Array1::from_shape_fn(a.dim().0, |i| zi.get(i).copied().unwrap_or_else(Zero::zero));
+                x.iter()
+                    .map(|&u| {
+                        let y = c.dot(&s) + *d*u;
+                        s = a.dot(&s) + b.mapv(|b| b*u);
+                        y
+                    }).collect()
+            }
+        }
+    }
+
+    /// Initial state for which the step response is in its steady state from the start.
+    fn zi(&self) -> Vec<Y>
+    {
+        match self
+        {
+            Realization::Cascade(sections) => {
+                let mut level = Y::one();
+                let mut zi = vec![];
+                for (b, a) in sections.iter()
+                {
+                    let sb = b.iter().fold(Y::zero(), |s, &b| s + b);
+                    let sa = a.iter().fold(Y::zero(), |s, &a| s + a);
+                    let g = sb/sa;
+                    let m = b.len() - 1;
+                    if ComplexFloat::is_finite(g)
+                    {
+                        // In the steady state, all past inputs of the section are at its input level, and all past outputs at
+                        // that level times its DC gain.
+                        zi.append(&mut Tf::new(b.clone(), a.clone()).filtic(vec![level*g; m], vec![level; m]));
+                        level = level*g
+                    }
+                    else
+                    {
+                        zi.extend((0..m).map(|_| Y::zero()));
+                        level = Y::zero()
+                    }
+                }
+                zi
+            },
+            Realization::StateSpace { a, b, .. } => {
+                let n = a.dim().0;
+                (Array2::<Y>::eye(n) - a).solve(b)
+                    .map(|s| s.to_vec())
+                    .unwrap_or_else(|_| vec![Y::zero(); n])
+            }
+        }
+    }
+
+    fn filtfilt(&self, x: &[Y], method: FiltFiltMethod) -> Vec<Y>
+    {
+        let n = x.len();
+        if n == 0
+        {
+            return vec![]
+        }
+        let order = self.order();
+
+        let (padding, length) = match method
+        {
+            FiltFiltMethod::Pad { padding, length } => (padding, length),
+            FiltFiltMethod::Gustafsson => return self.filtfilt_gustafsson(x)
+        };
+        let l = match padding
+        {
+            FiltFiltPadding::None => 0,
+            _ => length.unwrap_or(3*(order + 1))
+                .min(n - 1)
+        };
+
+        let (first, last) = (x[0], x[n - 1]);
+        let two = Y::one() + Y::one();
+        let (head, tail): (Vec<Y>, Vec<Y>) = match padding
+        {
+            FiltFiltPadding::Odd => (
+                x[1..=l].iter().rev().map(|&x| two*first - x).collect(),
+                x[n - 1 - l..n - 1].iter().rev().map(|&x| two*last - x).collect()
+            ),
+            FiltFiltPadding::Even => (
+                x[1..=l].iter().rev().copied().collect(),
+                x[n - 1 - l..n - 1].iter().rev().copied().collect()
+            ),
+            FiltFiltPadding::Constant => (vec![first; l], vec![last; l]),
+            FiltFiltPadding::None => (vec![], vec![])
+        };
+        let v: Vec<Y> = head.into_iter()
+            .chain(x.iter().copied())
+            .chain(tail)
+            .collect();
+
+        let zi = self.zi();
+        let scaled = |s: Y| -> Vec<Y> {
+            zi.iter()
+                .map(|&z| z*s)
+                .collect()
+        };
+        let mut y = self.filter(&v, &scaled(v[0]));
+        y.reverse();
+        let mut y = self.filter(&y, &scaled(y[0]));
+        y.reverse();
+
+        y[l..l + n].to_vec()
+    }
+
+    fn filtfilt_gustafsson(&self, x: &[Y]) -> Vec<Y>
+    {
+        let n = x.len();
+        let order = self.order();
+        let reversed = |mut x: Vec<Y>| {
+            x.reverse();
+            x
+        };
+        let zeros = vec![Y::zero(); n];
+
+        // Naive forward-backward and backward-forward results, from rest.
+        let y_fb = reversed(self.filter(&reversed(self.filter(x, &[])), &[]));
+        if order == 0
+        {
+            return y_fb
+        }
+        let y_bf = self.filter(&reversed(self.filter(&reversed(x.to_vec()), &[])), &[]);
+
+        // Zero-input responses to each unit initial state, and those responses reversed and filtered again.
+        let mut o = Array2::zeros((n, order));
+        let mut s = Array2::zeros((n, order));
+        for k in 0..order
+        {
+            let mut e = vec![Y::zero(); order];
+            e[k] = Y::one();
+            let ok = self.filter(&zeros, &e);
+            let sk = self.filter(&reversed(ok.clone()), &[]);
+            for i in 0..n
+            {
+                o[(i, k)] = ok[i];
+                s[(i, k)] = sk[i];
+            }
+        }
+        let flip = |m: &Array2<Y>| Array2::from_shape_fn(m.dim(), |(i, k)| m[(n - 1 - i, k)]);
+        let o_r = flip(&o);
+        let s_r = flip(&s);
+
+        let mut m = Array2::zeros((n, 2*order));
+        let mut w = Array2::zeros((n, 2*order));
+        for i in 0..n
+        {
+            for k in 0..order
+            {
+                m[(i, k)] = s_r[(i, k)] - o[(i, k)];
+                m[(i, order + k)] = o_r[(i, k)] - s[(i, k)];
+                w[(i, k)] = s_r[(i, k)];
+                w[(i, order + k)] = o_r[(i, k)];
+            }
+        }
+        let delta = Array1::from_shape_fn(n, |i| y_bf[i] - y_fb[i]);
+        let ic = util::pinv(m).dot(&delta);
+        let correction = w.dot(&ic);
+
+        y_fb.into_iter()
+            .zip(correction)
+            .map(|(y, c)| y + c)
+            .collect()
+    }
+}
+
+fn filtfilt_rows<X, XX, XXX, Y>(realization: &Realization<Y>, x: XX, method: FiltFiltMethod) -> XX::Mapped<Y>
+where
+    X: Into<Y>,
+    XX: Lists<X, RowOwned = XXX, RowsMapped<XXX::Mapped<Y>>: Into<XX::Mapped<Y>>>,
+    XXX: List<X, Mapped<()>: List<(), Mapped<Y> = XXX::Mapped<Y>>>,
+    Y: ComplexFloat + Lapack<Real = <Y as ComplexFloat>::Real> + Mul<<Y as ComplexFloat>::Real, Output = Y>,
+    <Y as ComplexFloat>::Real: Into<Y>,
+    Tf<Y, Vec<Y>, Vec<Y>>: FiltIc<Y, Vec<Y>, Vec<Y>>
+{
+    x.map_rows_into_owned(|x| {
+        let x_void = x.map_to_owned(|_| ());
+        let x: Vec<Y> = x.into_vec()
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        let mut y = realization.filtfilt(&x, method)
+            .into_iter();
+        x_void.map_into_owned(|()| y.next().unwrap())
+    }).into()
+}
+
+impl<T, B, A, X, Y, XX, XXX> FiltFiltWith<X, XX> for Tf<T, B, A>
+where
+    T: ComplexFloat + ComplexOp<X, Output = Y> + Into<Y>,
+    B: MaybeList<T>,
+    A: MaybeList<T>,
+    X: ComplexFloat<Real = T::Real> + Into<Y>,
+    XX: Lists<X, RowOwned = XXX, RowsMapped<XXX::Mapped<Y>>: Into<XX::Mapped<Y>>>,
+    XXX: List<X, Mapped<()>: List<(), Mapped<Y> = XXX::Mapped<Y>>>,
+    Y: ComplexFloat<Real = T::Real> + Lapack<Real = T::Real> + Mul<T::Real, Output = Y>,
+    T::Real: Into<Y>,
+    Tf<Y, Vec<Y>, Vec<Y>>: FiltIc<Y, Vec<Y>, Vec<Y>>
+{
+    fn filtfilt_with(&self, x: XX, method: FiltFiltMethod) -> XX::Mapped<Y>
+    {
+        let coeffs = |c: Option<&[T]>| c.map(|c| c.iter()
+                .map(|&c| c.into())
+                .collect()
+            ).unwrap_or_else(|| vec![Y::one()]);
+        let realization = Realization::cascade([(
+            coeffs(self.b.deref().as_view_slice_option()),
+            coeffs(self.a.deref().as_view_slice_option())
+        )]);
+        filtfilt_rows(&realization, x, method)
+    }
+}
+
+impl<T, B, A, S, X, Y, XX, XXX> FiltFiltWith<X, XX> for Sos<T, B, A, S>
+where
+    T: ComplexFloat + ComplexOp<X, Output = Y> + Into<Y>,
+    B: Maybe<[T; 3]> + MaybeOwnedList<T>,
+    A: Maybe<[T; 3]> + MaybeOwnedList<T>,
+    S: MaybeList<Tf<T, B, A>>,
+    X: ComplexFloat<Real = T::Real> + Into<Y>,
+    XX: Lists<X, RowOwned = XXX, RowsMapped<XXX::Mapped<Y>>: Into<XX::Mapped<Y>>>,
+    XXX: List<X, Mapped<()>: List<(), Mapped<Y> = XXX::Mapped<Y>>>,
+    Y: ComplexFloat<Real = T::Real> + Lapack<Real = T::Real> + Mul<T::Real, Output = Y>,
+    T::Real: Into<Y>,
+    Tf<Y, Vec<Y>, Vec<Y>>: FiltIc<Y, Vec<Y>, Vec<Y>>
+{
+    fn filtfilt_with(&self, x: XX, method: FiltFiltMethod) -> XX::Mapped<Y>
+    {
+        let zero = T::zero();
+        let one = T::one();
+        let coeffs = |c: Option<&[T; 3]>| c.copied()
+            .unwrap_or([zero, zero, one])
+            .into_iter()
+            .map(|c| c.into())
+            .collect::<Vec<Y>>();
+        let realization = Realization::cascade(self.sos.deref()
+            .as_view_slice_option()
+            .unwrap_or(&[])
+            .iter()
+            .map(|sos| (coeffs(sos.b.deref().as_option()), coeffs(sos.a.deref().as_option())))
+        );
+        filtfilt_rows(&realization, x, method)
+    }
+}
+
+impl<T, Z, P, K, X, XX, Y> FiltFiltWith<X, XX> for Zpk<T, Z, P, K>
+where
+    T: ComplexFloat<Real = K::Real>,
+    K: ComplexFloat + ComplexOp<X, Output = Y>,
+    Z: MaybeList<T>,
+    P: MaybeList<T>,
+    X: ComplexFloat<Real = K::Real> + Into<Y>,
+    XX: Lists<X>,
+    Y: ComplexFloat<Real = K::Real>,
+    Self: Clone + ToSos<K, [K; 3], [K; 3], Vec<Tf<K, [K; 3], [K; 3]>>, (), ()>,
+    Sos<K, [K; 3], [K; 3], Vec<Tf<K, [K; 3], [K; 3]>>>: FiltFiltWith<X, XX> + System<Set = K>
+{
+    fn filtfilt_with(&self, x: XX, method: FiltFiltMethod) -> XX::Mapped<Y>
+    {
+        // Pairing the poles and zeros into second-order sections keeps high orders numerically sound.
+        let sos: Sos<K, [K; 3], [K; 3], Vec<Tf<K, [K; 3], [K; 3]>>> = self.clone()
+            .to_sos((), ());
+        sos.filtfilt_with(x, method)
+    }
+}
+
+impl<T, A, B, C, D, X, Y, XX, XXX> FiltFiltWith<X, XX> for Ss<T, A, B, C, D>
+where
+    T: ComplexFloat + ComplexOp<X, Output = Y> + Into<Y>,
+    A: SsAMatrix<T, B, C, D>,
+    B: SsBMatrix<T, A, C, D>,
+    C: SsCMatrix<T, A, B, D>,
+    D: SsDMatrix<T, A, B, C>,
+    X: ComplexFloat<Real = T::Real> + Into<Y>,
+    XX: Lists<X, RowOwned = XXX, RowsMapped<XXX::Mapped<Y>>: Into<XX::Mapped<Y>>>,
+    XXX: List<X, Mapped<()>: List<(), Mapped<Y> = XXX::Mapped<Y>>>,
+    Y: ComplexFloat<Real = T::Real> + Lapack<Real = T::Real> + Mul<T::Real, Output = Y>,
+    T::Real: Into<Y>,
+    Tf<Y, Vec<Y>, Vec<Y>>: FiltIc<Y, Vec<Y>, Vec<Y>>
+{
+    fn filtfilt_with(&self, x: XX, method: FiltFiltMethod) -> XX::Mapped<Y>
+    {
+        // Only the first input and the first output are used.
+        let a = self.a.to_array2();
+        let b = self.b.to_array2();
+        let c = self.c.to_array2();
+        let d = self.d.to_array2();
+        let n = a.dim().0;
+        let realization = Realization::StateSpace {
+            a: a.mapv(|a| a.into()),
+            b: Array1::from_shape_fn(n, |i| b.get((i, 0)).map(|&b| b.into()).unwrap_or_else(Y::zero)),
+            c: Array1::from_shape_fn(n, |i| c.get((0, i)).map(|&c| c.into()).unwrap_or_else(Y::zero)),
+            d: d.get((0, 0)).map(|&d| d.into()).unwrap_or_else(Y::zero)
+        };
+        filtfilt_rows(&realization, x, method)
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use num::Complex;
+    use rand::distributions::uniform::SampleRange;
+
+    use crate::{gen::filter::{Butter, FilterGenPlane, FilterGenType}, operations::filtering::{FiltFilt, FiltFiltMethod, FiltFiltPadding, FiltFiltWith}, systems::{Sos, Ss, Tf, Zpk}};
+
+    #[test]
+    fn test()
+    {
+        let plane = FilterGenPlane::Z { sampling_frequency: None };
+        let sos: Sos<f64, [_; 3], [_; 3], Vec<_>> = Sos::butter(12, [0.1], FilterGenType::LowPass, plane)
+            .unwrap();
+        let zpk: Zpk<Complex<f64>, Vec<_>, Vec<_>, f64> = Zpk::butter(12, [0.1], FilterGenType::LowPass, plane)
+            .unwrap();
+        let ss: Ss<f64, _, _, _, _> = Ss::butter(4, [0.1], FilterGenType::LowPass, plane)
+            .unwrap();
+
+        // A constant is passed through unchanged, edges included.
+        let x = vec![1.0; 200];
+        for method in [
+            FiltFiltMethod::default(),
+            FiltFiltMethod::Pad { padding: FiltFiltPadding::Even, length: Some(100) },
+            FiltFiltMethod::Gustafsson
+        ]
+        {
+            let y1: Vec<f64> = sos.filtfilt_with(x.clone(), method);
+            let y2: Vec<f64> = zpk.filtfilt_with(x.clone(), method);
+            let y3: Vec<f64> = ss.filtfilt_with(x.clone(), method);
+            for ((y1, y2), y3) in y1.into_iter()
+                .zip(y2)
+                .zip(y3)
+            {
+                assert!((y1 - 1.0).abs() < 1e-3);
+                assert!((y2 - 1.0).abs() < 1e-3);
+                assert!((y3 - 1.0).abs() < 1e-3);
+            }
+        }
+
+        // A low-order design gives the same result as the transfer function, which is padded by three times its order.
+        let tf: Tf<f64, Vec<_>, Vec<_>> = Tf::butter(2, [0.2], FilterGenType::LowPass, plane)
+            .unwrap();
+        let sos: Sos<f64, [_; 3], [_; 3], Vec<_>> = Sos::butter(2, [0.2], FilterGenType::LowPass, plane)
+            .unwrap();
+        let zpk: Zpk<Complex<f64>, Vec<_>, Vec<_>, f64> = Zpk::butter(2, [0.2], FilterGenType::LowPass, plane)
+            .unwrap();
+        let ss: Ss<f64, _, _, _, _> = Ss::butter(2, [0.2], FilterGenType::LowPass, plane)
+            .unwrap();
+
+        let mut rng = rand::thread_rng();
+        let x: Vec<f64> = (0..64).map(|_| (-1.0..1.0).sample_single(&mut rng))
+            .collect();
+        let method = FiltFiltMethod::Pad { padding: FiltFiltPadding::Odd, length: Some(6) };
+
+        let y: Vec<f64> = tf.filtfilt(x.clone());
+        let y_with: [Vec<f64>; 3] = [
+            sos.filtfilt_with(x.clone(), method),
+            zpk.filtfilt_with(x.clone(), method),
+            ss.filtfilt_with(x, method)
+        ];
+        for y_with in y_with
+        {
+            for (&y, y_with) in y.iter()
+                .zip(y_with)
+            {
+                assert!((y - y_with).abs() < 1e-9);
+            }
+        }
+    }
+}
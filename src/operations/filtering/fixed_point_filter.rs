@@ -0,0 +1,320 @@
+use num::Float;
+use thiserror::Error;
+
+use crate::{operations::{EncodeOverflow, FixedPointFormat, QuantizeRounding}, quantities::{List, MaybeList}, systems::{Sos, Tf}};
+
+/// Widest accepted accumulator, in bits.
+pub const MAX_ACCUMULATOR_WORD_LENGTH: u32 = 126;
+
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum FixedPointFilterError
+{
+    #[error("Signal and coefficient word lengths must be between 1 and 64 bits.")]
+    WordLengthOutOfRange,
+    #[error("Signal and coefficient fraction lengths must be at most 63 bits.")]
+    FractionLengthOutOfRange,
+    #[error("Accumulator must be at most 126 bits, and at least as wide as a product of a signal sample and a coefficient.")]
+    AccumulatorWidthOutOfRange,
+    #[error("Leading denominator coefficient must be non-zero.")]
+    ZeroLeadingCoefficient
+}
+
+/// Arithmetic of a [FixedPointFilter].
+///
+/// Products of signal samples and coefficients are accumulated at full precision, i.e. with the sum of the signal and coefficient
+/// fraction lengths, in an accumulator of `accumulator_word_length` bits. Overflow of the accumulator, and of every sample stored
+/// or output, is handled according to `overflow`. The accumulator is scaled back to the signal format according to `rounding`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedPointConfig
+{
+    pub signal: FixedPointFormat,
+    pub coefficient: FixedPointFormat,
+    pub accumulator_word_length: u32,
+    pub rounding: QuantizeRounding,
+    pub overflow: EncodeOverflow
+}
+
+impl FixedPointConfig
+{
+    /// Q15 signals and coefficients, with a 40-bit accumulator, rounding and saturation, as on common 16-bit DSPs.
+    pub const Q15: Self = Self {
+        signal: FixedPointFormat::Q15,
+        coefficient: FixedPointFormat::Q15,
+        accumulator_word_length: 40,
+        rounding: QuantizeRounding::Nearest,
+        overflow: EncodeOverflow::Saturate
+    };
+    /// Q31 signals and coefficients, with a 64-bit accumulator, rounding and saturation.
+    pub const Q31: Self = Self {
+        signal: FixedPointFormat::Q31,
+        coefficient: FixedPointFormat::Q31,
+        accumulator_word_length: 64,
+        rounding: QuantizeRounding::Nearest,
+        overflow: EncodeOverflow::Saturate
+    };
+
+    fn accumulator(&self) -> FixedPointFormat
+    {
+        FixedPointFormat {
+            word_length: self.accumulator_word_length,
+            fraction_length: self.signal.fraction_length + self.coefficient.fraction_length
+        }
+    }
+}
+
+/// Shifts right by `shift` bits, rounding the discarded bits according to `rounding`.
+fn shift_right(x: i128, shift: u32, rounding: QuantizeRounding) -> i128
+{
+    if shift == 0
+    {
+        return x
+    }
+    let floor = x >> shift;
+    let remainder = x - (floor << shift);
+    let half = 1i128 << (shift - 1);
+    match rounding
+    {
+        QuantizeRounding::Floor => floor,
+        QuantizeRounding::Ceil => if remainder != 0 {floor + 1} else {floor},
+        QuantizeRounding::Zero => if x < 0 && remainder != 0 {floor + 1} else {floor},
+        QuantizeRounding::Nearest => if remainder > half || (remainder == half && x >= 0) {floor + 1} else {floor},
+        QuantizeRounding::Convergent => if remainder > half || (remainder == half && floor % 2 != 0) {floor + 1} else {floor}
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Section
+{
+    b: Vec<i128>,
+    /// Denominator coefficients a_1 to a_N.
+    a: Vec<i128>,
+    x: Vec<i128>,
+    y: Vec<i128>
+}
+
+/// Bit-accurate fixed-point filter.
+///
+/// FIR filters are realized in direct form, and second-order sections each in direct form I, with the output of every section
+/// quantized to the signal format. The input history of every section is kept between calls.
+#[derive(Debug, Clone)]
+pub struct FixedPointFilter
+{
+    pub config: FixedPointConfig,
+    sections: Vec<Section>
+}
+
+impl FixedPointFilter
+{
+    fn new<T>(config: FixedPointConfig, sections: Vec<(&[T], &[T])>) -> Result<Self, FixedPointFilterError>
+    where
+        T: Float
+    {
+        let FixedPointConfig { signal, coefficient, accumulator_word_length, rounding, overflow } = config;
+        if !(1..=64).contains(&signal.word_length) || !(1..=64).contains(&coefficient.word_length)
+        {
+            return Err(FixedPointFilterError::WordLengthOutOfRange)
+        }
+        // Keeps the accumulator's fraction length, and the shift back to the signal format, within an i128.
+        if signal.fraction_length > 63 || coefficient.fraction_length > 63
+        {
+            return Err(FixedPointFilterError::FractionLengthOutOfRange)
+        }
+        // With at most 126 bits, adding a product to the accumulator cannot overflow the i128 it is computed in.
+        if accumulator_word_length > MAX_ACCUMULATOR_WORD_LENGTH || accumulator_word_length < signal.word_length + coefficient.word_length - 1
+        {
+            return Err(FixedPointFilterError::AccumulatorWidthOutOfRange)
+        }
+
+        let sections = sections.into_iter()
+            .map(|(b, a)| {
+                let a0 = a.first()
+                    .copied()
+                    .unwrap_or_else(T::one);
+                if a0.is_zero()
+                {
+                    return Err(FixedPointFilterError::ZeroLeadingCoefficient)
+                }
+                let q = |c: &T| coefficient.to_integer(*c/a0, rounding, overflow);
+                let b: Vec<i128> = b.iter()
+                    .map(q)
+                    .collect();
+                let a: Vec<i128> = a.iter()
+                    .skip(1)
+                    .map(q)
+                    .collect();
+                Ok(Section {
+                    x: vec![0; b.len()],
+                    y: vec![0; a.len()],
+                    b,
+                    a
+                })
+            }).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            config,
+            sections
+        })
+    }
+
+    pub fn from_fir<T, B>(h: &Tf<T, B, ()>, config: FixedPointConfig) -> Result<Self, FixedPointFilterError>
+    where
+        T: Float,
+        B: List<T>
+    {
+        Self::new(config, vec![(h.b.as_view_slice(), &[T::one()][..])])
+    }
+
+    pub fn from_sos<T, S>(h: &Sos<T, [T; 3], [T; 3], S>, config: FixedPointConfig) -> Result<Self, FixedPointFilterError>
+    where
+        T: Float,
+        S: MaybeList<Tf<T, [T; 3], [T; 3]>>
+    {
+        let sections = h.sos.as_view_slice_option()
+            .unwrap_or(&[])
+            .iter()
+            .map(|tf| (&tf.b[..], &tf.a[..]))
+            .collect();
+        Self::new(config, sections)
+    }
+
+    /// Filters integer samples in the signal format.
+    pub fn filter_raw(&mut self, x: &[i64]) -> Vec<i64>
+    {
+        let FixedPointConfig { signal, coefficient, rounding, overflow, .. } = self.config;
+        let accumulator = self.config.accumulator();
+
+        x.iter()
+            .map(|&x| {
+                let mut x = signal.overflow(x as i128, overflow);
+                for section in self.sections.iter_mut()
+                {
+                    section.x.rotate_right(1);
+                    if let Some(x0) = section.x.first_mut()
+                    {
+                        *x0 = x
+                    }
+
+                    let mut acc = 0i128;
+                    for (&b, &x) in section.b.iter()
+                        .zip(section.x.iter())
+                    {
+                        acc = accumulator.overflow(acc + b*x, overflow)
+                    }
+                    for (&a, &y) in section.a.iter()
+                        .zip(section.y.iter())
+                    {
+                        acc = accumulator.overflow(acc - a*y, overflow)
+                    }
+
+                    x = signal.overflow(shift_right(acc, coefficient.fraction_length, rounding), overflow);
+                    section.y.rotate_right(1);
+                    if let Some(y0) = section.y.first_mut()
+                    {
+                        *y0 = x
+                    }
+                }
+                x as i64
+            }).collect()
+    }
+
+    /// Quantizes the samples to the signal format, filters them, and converts the result back to floating point.
+    pub fn filter<T>(&mut self, x: &[T]) -> Vec<T>
+    where
+        T: Float
+    {
+        let FixedPointConfig { signal, rounding, overflow, .. } = self.config;
+
+        let x: Vec<i64> = x.iter()
+            .map(|&x| signal.to_integer(x, rounding, overflow) as i64)
+            .collect();
+        self.filter_raw(&x)
+            .into_iter()
+            .map(|y| signal.to_float(y as i128))
+            .collect()
+    }
+
+    /// Clears the filter states.
+    pub fn reset(&mut self)
+    {
+        for section in self.sections.iter_mut()
+        {
+            section.x.fill(0);
+            section.y.fill(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use crate::{gen::filter::{Butter, FilterGenPlane, FilterGenType}, operations::{filtering::{FixedPointConfig, FixedPointFilter, FixedPointFilterError, MAX_ACCUMULATOR_WORD_LENGTH}, EncodeOverflow, FixedPointFormat}, systems::{Sos, Tf}};
+
+    #[test]
+    fn test()
+    {
+        // Two taps of gain one overflow the Q15 output for an input of 0.75.
+        let h = Tf::new([1.0, 1.0], ());
+        let config = FixedPointConfig {
+            coefficient: FixedPointFormat::q(1, 14),
+            ..FixedPointConfig::Q15
+        };
+        let mut saturate = FixedPointFilter::from_fir(&h, config)
+            .unwrap();
+        let mut wrap = FixedPointFilter::from_fir(&h, FixedPointConfig {
+                overflow: EncodeOverflow::Wrap,
+                ..config
+            }).unwrap();
+        assert_eq!(saturate.filter_raw(&[24576, 24576]), [24576, 32767]);
+        assert_eq!(wrap.filter_raw(&[24576, 24576]), [24576, -16384]);
+
+        // The step response of a low-pass settles at the step amplitude.
+        let h: Sos<f64, [_; 3], [_; 3], Vec<_>> = Sos::butter(4, [0.2], FilterGenType::LowPass, FilterGenPlane::Z { sampling_frequency: None })
+            .unwrap();
+        let mut filter = FixedPointFilter::from_sos(&h, config)
+            .unwrap();
+        let y = filter.filter(&[0.5; 200]);
+        assert!((y.last().unwrap() - 0.5).abs() < 1e-2);
+
+        // The widest accumulator neither overflows nor panics, in either overflow mode, at full-scale input.
+        for overflow in [EncodeOverflow::Saturate, EncodeOverflow::Wrap]
+        {
+            let config = FixedPointConfig {
+                coefficient: FixedPointFormat::q(2, 29),
+                accumulator_word_length: MAX_ACCUMULATOR_WORD_LENGTH,
+                overflow,
+                ..FixedPointConfig::Q31
+            };
+            let mut filter = FixedPointFilter::from_sos(&h, config)
+                .unwrap();
+            let y = filter.filter(&[-1.0, 0.999, -1.0, 0.999]);
+            assert!(y.iter().all(|y| y.is_finite() && y.abs() <= 1.0));
+            let y = filter.filter(&[0.5; 200]);
+            assert!((y.last().unwrap() - 0.5).abs() < 1e-2);
+
+            assert_eq!(
+                FixedPointFilter::from_sos(&h, FixedPointConfig {
+                    accumulator_word_length: MAX_ACCUMULATOR_WORD_LENGTH + 1,
+                    ..config
+                }).err(),
+                Some(FixedPointFilterError::AccumulatorWidthOutOfRange)
+            );
+        }
+
+        // Fraction lengths that would shift the accumulator out of an i128 are rejected instead of panicking.
+        for (signal, coefficient) in [
+            (FixedPointFormat { word_length: 32, fraction_length: 200 }, FixedPointFormat::Q31),
+            (FixedPointFormat::Q31, FixedPointFormat { word_length: 32, fraction_length: 128 }),
+            (FixedPointFormat::Q31, FixedPointFormat { word_length: 32, fraction_length: u32::MAX })
+        ]
+        {
+            assert_eq!(
+                FixedPointFilter::from_sos(&h, FixedPointConfig {
+                    signal,
+                    coefficient,
+                    ..FixedPointConfig::Q31
+                }).err(),
+                Some(FixedPointFilterError::FractionLengthOutOfRange)
+            );
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use num::{Float, NumCast};
+use option_trait::Maybe;
+
+use crate::{operations::filtering::OrdFiltPadding, quantities::{ListOrSingle, Lists, OwnedList}};
+
+pub trait Hampel<T>: Lists<T>
+where
+    T: Float
+{
+    /// Hampel outlier filter.
+    ///
+    /// Each sample is compared to the median of the `2*k + 1` samples around it, truncated at the edges of the signal. Samples
+    /// deviating from the median by more than `nsigma` times the scaled median absolute deviation of the window are outliers, and
+    /// are replaced with the median. Defaults are `k = 3` and `nsigma = 3`.
+    ///
+    /// Returns the cleaned signal and the indices of the outliers.
+    fn hampel<K, N>(self, k: K, nsigma: N) -> Self::RowsMapped<(Self::RowOwned, Vec<usize>)>
+    where
+        K: Maybe<usize>,
+        N: Maybe<T>;
+}
+
+impl<T, L> Hampel<T> for L
+where
+    T: Float,
+    L: Lists<T, RowOwned: OwnedList<T>>
+{
+    fn hampel<K, N>(self, k: K, nsigma: N) -> Self::RowsMapped<(Self::RowOwned, Vec<usize>)>
+    where
+        K: Maybe<usize>,
+        N: Maybe<T>
+    {
+        let k = k.into_option()
+            .unwrap_or(3);
+        let nsigma = nsigma.into_option()
+            .unwrap_or_else(|| <T as NumCast>::from(3.0).unwrap());
+        // Scales the median absolute deviation to the standard deviation of normally distributed samples.
+        let scale = <T as NumCast>::from(1.4826).unwrap();
+
+        self.map_rows_into_owned(|mut x| {
+            let mut outliers = vec![];
+            let mut deviations = Vec::with_capacity(2*k + 1);
+            let v: &[T] = x.as_mut_slice();
+            let mut i = 0;
+            let y = OrdFiltPadding::Truncate.sliding(v, 2*k + 1, |w| {
+                let x = v[i];
+                let median = w.median()
+                    .unwrap_or(x);
+
+                deviations.clear();
+                deviations.extend(w.as_slice()
+                    .iter()
+                    .map(|&w| (w - median).abs())
+                );
+                deviations.sort_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+                let n = deviations.len();
+                let mad = if n % 2 == 1
+                {
+                    deviations[n/2]
+                }
+                else
+                {
+                    (deviations[n/2 - 1] + deviations[n/2])/(T::one() + T::one())
+                };
+
+                let y = if (x - median).abs() > nsigma*scale*mad
+                {
+                    outliers.push(i);
+                    median
+                }
+                else
+                {
+                    x
+                };
+                i += 1;
+                y
+            });
+            x.as_mut_slice()
+                .copy_from_slice(&y);
+            (x, outliers)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::TAU;
+
+    use crate::operations::filtering::Hampel;
+
+    #[test]
+    fn test()
+    {
+        const N: usize = 500;
+
+        let x: Vec<f64> = (0..N).map(|i| (TAU*4.0*i as f64/N as f64).sin())
+            .collect();
+        let mut z = x.clone();
+        let spikes = [17, 130, 256, 401];
+        for &i in spikes.iter()
+        {
+            z[i] += 5.0
+        }
+
+        let (y, outliers) = z.hampel((), ());
+
+        assert_eq!(outliers, spikes);
+        for (y, x) in y.into_iter()
+            .zip(x)
+        {
+            assert!((y - x).abs() < 0.1)
+        }
+    }
+}
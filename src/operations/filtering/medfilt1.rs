@@ -0,0 +1,65 @@
+use num::Float;
+
+use crate::{operations::filtering::OrdFiltPadding, quantities::{ListOrSingle, Lists, OwnedList}};
+
+pub trait MedFilt1<T>: Lists<T>
+where
+    T: Float
+{
+    /// Running median filter.
+    ///
+    /// Each output sample is the median of the `n` input samples around it. For even `n`, the window spans one more sample before
+    /// than after, and the median is the mean of the two middle samples. Impulses shorter than half the window are removed, while
+    /// steps are preserved.
+    fn medfilt1(self, n: usize, padding: OrdFiltPadding) -> Self::RowsMapped<Self::RowOwned>;
+}
+
+impl<T, L> MedFilt1<T> for L
+where
+    T: Float,
+    L: Lists<T, RowOwned: OwnedList<T>>
+{
+    fn medfilt1(self, n: usize, padding: OrdFiltPadding) -> Self::RowsMapped<Self::RowOwned>
+    {
+        let n = n.max(1);
+        self.map_rows_into_owned(|mut x| {
+            let y = padding.sliding(x.as_mut_slice(), n, |w| w.median()
+                .unwrap_or_else(T::nan)
+            );
+            x.as_mut_slice()
+                .copy_from_slice(&y);
+            x
+        })
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::TAU;
+
+    use crate::operations::filtering::{MedFilt1, OrdFiltPadding};
+
+    #[test]
+    fn test()
+    {
+        const N: usize = 200;
+
+        let x: Vec<f64> = (0..N).map(|i| (TAU*i as f64/N as f64).sin())
+            .collect();
+        let mut z = x.clone();
+        for i in (5..N).step_by(20)
+        {
+            z[i] += 10.0
+        }
+
+        let y = z.medfilt1(5, OrdFiltPadding::Truncate);
+
+        // The impulses are removed, and the slowly varying sine changes by at most a sample step.
+        for (y, x) in y.into_iter()
+            .zip(x)
+        {
+            assert!((y - x).abs() < 0.05)
+        }
+    }
+}
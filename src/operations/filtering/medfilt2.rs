@@ -0,0 +1,63 @@
+use ndarray::Array2;
+use num::Float;
+use option_trait::Maybe;
+
+use crate::operations::filtering::OrdFiltPadding;
+
+pub trait MedFilt2<T>: Sized
+where
+    T: Float
+{
+    /// 2-D running median filter.
+    ///
+    /// Each output sample is the median of the `window` (rows by columns) input samples around it. Default window is 3 by 3.
+    fn medfilt2<W>(self, window: W, padding: OrdFiltPadding) -> Array2<T>
+    where
+        W: Maybe<[usize; 2]>;
+}
+
+impl<T> MedFilt2<T> for Array2<T>
+where
+    T: Float
+{
+    fn medfilt2<W>(self, window: W, padding: OrdFiltPadding) -> Array2<T>
+    where
+        W: Maybe<[usize; 2]>
+    {
+        let window = window.into_option()
+            .unwrap_or([3, 3])
+            .map(|n| n.max(1));
+
+        padding.sliding_2d(&self, window, |w| w.median()
+            .unwrap_or_else(T::nan)
+        )
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use ndarray::Array2;
+
+    use crate::operations::filtering::{MedFilt2, OrdFiltPadding};
+
+    #[test]
+    fn test()
+    {
+        let x = Array2::from_shape_fn((32, 32), |(i, j)| (i + j) as f64/64.0);
+        let mut z = x.clone();
+        for (i, j) in [(3, 4), (10, 20), (25, 7), (30, 30)]
+        {
+            z[(i, j)] = 100.0
+        }
+
+        let y = z.medfilt2((), OrdFiltPadding::Truncate);
+
+        // Salt noise is removed from the plane.
+        for (y, x) in y.into_iter()
+            .zip(x)
+        {
+            assert!((y - x).abs() < 0.05)
+        }
+    }
+}
@@ -1,9 +1,20 @@
 moddef::moddef!(
     flat(pub) mod {
+        adaptive_filter,
         fftfilt,
         filter_mut,
         filter,
+        filtfilt_with,
         filtfilt,
+        fixed_point_filter,
+        hampel,
+        medfilt1,
+        medfilt2,
+        ordfilt1,
+        ordfilt2,
+        partitioned_convolver,
+        sgolay_deriv,
+        sgolayfilt_2d,
         sgolayfilt
     }
 );
\ No newline at end of file
@@ -0,0 +1,225 @@
+use ndarray::Array2;
+use num::{Float, NumCast};
+use thiserror::Error;
+
+use crate::{quantities::{ListOrSingle, Lists, OwnedList}, util::SortedWindow};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum OrdFiltError
+{
+    #[error("Window must contain at least one sample.")]
+    WindowIsEmpty,
+    #[error("Rank must be less than the number of samples in the window.")]
+    RankOutOfRange
+}
+
+/// How the window is completed where it extends beyond the edges of the signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OrdFiltPadding
+{
+    /// The signal is extended with zeros.
+    #[default]
+    Zero,
+    /// The window is truncated to the samples of the signal.
+    Truncate
+}
+
+impl OrdFiltPadding
+{
+    /// Slides a window of `n` samples along `x`, and evaluates `f` on each window.
+    ///
+    /// The window of output sample `k` spans input samples `k - n/2` to `k - n/2 + n - 1`. Each step removes and inserts one
+    /// sample in the sorted window, instead of sorting the window anew.
+    pub(crate) fn sliding<T, F>(self, x: &[T], n: usize, mut f: F) -> Vec<T>
+    where
+        T: Float,
+        F: FnMut(&SortedWindow<T>) -> T
+    {
+        let get = |i: isize| if i >= 0 && (i as usize) < x.len()
+        {
+            Some(x[i as usize])
+        }
+        else
+        {
+            match self
+            {
+                Self::Zero => Some(T::zero()),
+                Self::Truncate => None
+            }
+        };
+
+        let mut window = SortedWindow::with_capacity(n);
+        let start = -((n/2) as isize);
+        for i in start..start + n as isize
+        {
+            if let Some(x) = get(i)
+            {
+                window.insert(x)
+            }
+        }
+
+        let mut y = Vec::with_capacity(x.len());
+        for k in 0..x.len() as isize
+        {
+            y.push(f(&window));
+            if let Some(x) = get(k + start)
+            {
+                window.remove(x);
+            }
+            if let Some(x) = get(k + start + n as isize)
+            {
+                window.insert(x)
+            }
+        }
+        y
+    }
+
+    /// Slides a window of `m` by `n` samples along `x`, and evaluates `f` on each window.
+    ///
+    /// Along each row, a column of the window is removed and another inserted at each step.
+    pub(crate) fn sliding_2d<T, F>(self, x: &Array2<T>, [m, n]: [usize; 2], mut f: F) -> Array2<T>
+    where
+        T: Float,
+        F: FnMut(&SortedWindow<T>) -> T
+    {
+        let (h, w) = x.dim();
+        let get = |i: isize, j: isize| if i >= 0 && (i as usize) < h && j >= 0 && (j as usize) < w
+        {
+            Some(x[(i as usize, j as usize)])
+        }
+        else
+        {
+            match self
+            {
+                Self::Zero => Some(T::zero()),
+                Self::Truncate => None
+            }
+        };
+
+        let row_start = -((m/2) as isize);
+        let col_start = -((n/2) as isize);
+        let mut window = SortedWindow::with_capacity(m*n);
+        let mut y = Array2::from_elem((h, w), T::zero());
+        for r in 0..h as isize
+        {
+            window.clear();
+            let rows = r + row_start..r + row_start + m as isize;
+            for i in rows.clone()
+            {
+                for j in col_start..col_start + n as isize
+                {
+                    if let Some(x) = get(i, j)
+                    {
+                        window.insert(x)
+                    }
+                }
+            }
+            for c in 0..w as isize
+            {
+                y[(r as usize, c as usize)] = f(&window);
+                for i in rows.clone()
+                {
+                    if let Some(x) = get(i, c + col_start)
+                    {
+                        window.remove(x);
+                    }
+                    if let Some(x) = get(i, c + col_start + n as isize)
+                    {
+                        window.insert(x)
+                    }
+                }
+            }
+        }
+        y
+    }
+
+    /// Rank within a window of `len` samples corresponding to `rank` within a complete window of `n` samples.
+    pub(crate) fn rank(self, rank: usize, len: usize, n: usize) -> usize
+    {
+        match self
+        {
+            Self::Zero => rank,
+            Self::Truncate => if n <= 1
+            {
+                0
+            }
+            else
+            {
+                <usize as NumCast>::from(((rank*(len - 1)) as f64/(n - 1) as f64).round()).unwrap()
+            }
+        }
+    }
+}
+
+pub trait OrdFilt1<T>: Lists<T>
+where
+    T: Float
+{
+    /// Rank-order filter.
+    ///
+    /// Each output sample is the `rank`-th smallest, starting at zero, of the `n` input samples around it. A rank of zero gives
+    /// a running minimum, `n - 1` a running maximum, and `(n - 1)/2` a running median for odd `n`.
+    fn ordfilt1(self, rank: usize, n: usize, padding: OrdFiltPadding) -> Result<Self::RowsMapped<Self::RowOwned>, OrdFiltError>;
+}
+
+impl<T, L> OrdFilt1<T> for L
+where
+    T: Float,
+    L: Lists<T, RowOwned: OwnedList<T>>
+{
+    fn ordfilt1(self, rank: usize, n: usize, padding: OrdFiltPadding) -> Result<Self::RowsMapped<Self::RowOwned>, OrdFiltError>
+    {
+        if n == 0
+        {
+            return Err(OrdFiltError::WindowIsEmpty)
+        }
+        if rank >= n
+        {
+            return Err(OrdFiltError::RankOutOfRange)
+        }
+
+        Ok(self.map_rows_into_owned(|mut x| {
+            let y = padding.sliding(x.as_mut_slice(), n, |w| w.get(padding.rank(rank, w.len(), n))
+                .unwrap_or_else(T::nan)
+            );
+            x.as_mut_slice()
+                .copy_from_slice(&y);
+            x
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use rand::distributions::uniform::SampleRange;
+
+    use crate::operations::filtering::{OrdFilt1, OrdFiltPadding};
+
+    #[test]
+    fn test()
+    {
+        const N: usize = 7;
+
+        let mut rng = rand::thread_rng();
+        let x: Vec<f64> = (0..100).map(|_| (-1.0..1.0).sample_single(&mut rng))
+            .collect();
+
+        for rank in [0, 2, N - 1]
+        {
+            let y = x.clone()
+                .ordfilt1(rank, N, OrdFiltPadding::Truncate)
+                .unwrap();
+
+            // Equal to sorting each window.
+            for (k, y) in y.into_iter()
+                .enumerate()
+            {
+                let mut w = x[k.saturating_sub(N/2)..(k + N - N/2).min(x.len())].to_vec();
+                w.sort_by(f64::total_cmp);
+                let r = OrdFiltPadding::Truncate.rank(rank, w.len(), N);
+                assert_eq!(y, w[r]);
+            }
+        }
+    }
+}
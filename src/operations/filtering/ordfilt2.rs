@@ -0,0 +1,65 @@
+use ndarray::Array2;
+use num::Float;
+
+use crate::operations::filtering::{OrdFiltError, OrdFiltPadding};
+
+pub trait OrdFilt2<T>: Sized
+where
+    T: Float
+{
+    /// 2-D rank-order filter.
+    ///
+    /// Each output sample is the `rank`-th smallest, starting at zero, of the `window` (rows by columns) input samples around it.
+    fn ordfilt2(self, rank: usize, window: [usize; 2], padding: OrdFiltPadding) -> Result<Array2<T>, OrdFiltError>;
+}
+
+impl<T> OrdFilt2<T> for Array2<T>
+where
+    T: Float
+{
+    fn ordfilt2(self, rank: usize, window: [usize; 2], padding: OrdFiltPadding) -> Result<Array2<T>, OrdFiltError>
+    {
+        let [m, n] = window;
+        if m*n == 0
+        {
+            return Err(OrdFiltError::WindowIsEmpty)
+        }
+        if rank >= m*n
+        {
+            return Err(OrdFiltError::RankOutOfRange)
+        }
+
+        Ok(padding.sliding_2d(&self, window, |w| w.get(padding.rank(rank, w.len(), m*n))
+            .unwrap_or_else(T::nan)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use ndarray::Array2;
+
+    use crate::operations::filtering::{OrdFilt2, OrdFiltPadding};
+
+    #[test]
+    fn test()
+    {
+        let x = Array2::from_shape_fn((8, 10), |(i, j)| ((3*i + 7*j) % 11) as f64);
+
+        // Running minimum and maximum over 3x3 neighbourhoods.
+        let min = x.clone()
+            .ordfilt2(0, [3, 3], OrdFiltPadding::Truncate)
+            .unwrap();
+        let max = x.clone()
+            .ordfilt2(8, [3, 3], OrdFiltPadding::Truncate)
+            .unwrap();
+        for ((i, j), &x) in x.indexed_iter()
+        {
+            let neighbours = || (i.saturating_sub(1)..(i + 2).min(8)).flat_map(move |r| (j.saturating_sub(1)..(j + 2).min(10)).map(move |c| (r, c)));
+            assert!(min[(i, j)] <= x && max[(i, j)] >= x);
+            assert!(neighbours().all(|(r, c)| min[(i, j)] <= ((3*r + 7*c) % 11) as f64));
+            assert!(neighbours().all(|(r, c)| max[(i, j)] >= ((3*r + 7*c) % 11) as f64));
+        }
+    }
+}
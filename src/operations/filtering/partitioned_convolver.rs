@@ -0,0 +1,350 @@
+use core::ops::{AddAssign, MulAssign};
+use std::collections::VecDeque;
+
+use array_math::SliceMath;
+use num::{Complex, Float, Zero};
+use thiserror::Error;
+
+use crate::{quantities::List, systems::Tf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum PartitionedConvolverError
+{
+    #[error("At least one partition size must be given, and partition sizes must be non-zero.")]
+    InvalidPartitionSize,
+    #[error("Every partition size must be a multiple of the first partition size.")]
+    PartitionNotMultipleOfBlockSize,
+    #[error("A partition of size P must start at least P minus the block size samples into the impulse response.")]
+    PartitionTooEarly
+}
+
+/// Block convolution method of a [PartitionedConvolver].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConvolutionMethod
+{
+    /// Zero-padded input blocks, with the tail of every block added onto the next.
+    OverlapAdd,
+    /// Overlapping input blocks, with the circularly aliased half of every block discarded.
+    #[default]
+    OverlapSave
+}
+
+/// Uniformly partitioned segment of the impulse response, with its own frequency-domain delay line.
+#[derive(Debug, Clone)]
+struct Stage<T>
+where
+    T: Float
+{
+    partition_size: usize,
+    /// Number of leading partitions that are all zero, and therefore left out.
+    skip: usize,
+    partitions: Vec<Vec<Complex<T>>>,
+    delay_line: VecDeque<Vec<Complex<T>>>,
+    input: Vec<T>,
+    /// Previous input block for overlap-save, or the tail of the previous output block for overlap-add.
+    overlap: Vec<T>,
+    output: VecDeque<T>
+}
+
+impl<T> Stage<T>
+where
+    T: Float,
+    Complex<T>: AddAssign + MulAssign + MulAssign<T>
+{
+    fn new(h: &[T], partition_size: usize, block_size: usize) -> Self
+    {
+        let p = partition_size;
+        let skip = h.iter()
+            .take_while(|h| h.is_zero())
+            .count()/p;
+        let partitions: Vec<Vec<Complex<T>>> = h.chunks(p)
+            .skip(skip)
+            .map(|h| {
+                let mut h: Vec<Complex<T>> = h.iter()
+                    .map(|&h| h.into())
+                    .collect();
+                h.resize(2*p, Zero::zero());
+                h.fft();
+                h
+            }).collect();
+        let mut stage = Self {
+            partition_size,
+            skip,
+            delay_line: VecDeque::new(),
+            partitions,
+            input: vec![],
+            overlap: vec![],
+            output: VecDeque::new()
+        };
+        stage.reset(block_size);
+        stage
+    }
+
+    fn reset(&mut self, block_size: usize)
+    {
+        let p = self.partition_size;
+        self.delay_line = (0..self.skip + self.partitions.len())
+            .map(|_| vec![Zero::zero(); 2*p])
+            .collect();
+        self.input.clear();
+        self.overlap = vec![T::zero(); p];
+        // Nothing from this stage reaches the output until its first partition has been filled.
+        self.output = core::iter::repeat(T::zero())
+            .take(p - block_size)
+            .collect();
+    }
+
+    fn push(&mut self, x: &[T], method: ConvolutionMethod)
+    {
+        let p = self.partition_size;
+        self.input.extend_from_slice(x);
+        if self.input.len() < p
+        {
+            return
+        }
+
+        let mut xf: Vec<Complex<T>> = match method
+        {
+            ConvolutionMethod::OverlapAdd => self.input.iter()
+                .map(|&x| x.into())
+                .chain(core::iter::repeat(Zero::zero()).take(p))
+                .collect(),
+            ConvolutionMethod::OverlapSave => self.overlap.iter()
+                .chain(self.input.iter())
+                .map(|&x| x.into())
+                .collect()
+        };
+        xf.fft();
+        if method == ConvolutionMethod::OverlapSave
+        {
+            core::mem::swap(&mut self.overlap, &mut self.input);
+        }
+        self.input.clear();
+
+        self.delay_line.pop_back();
+        self.delay_line.push_front(xf);
+
+        let mut y = vec![Complex::zero(); 2*p];
+        for (h, x) in self.partitions.iter()
+            .zip(self.delay_line.iter()
+                .skip(self.skip)
+            )
+        {
+            for ((y, &h), &x) in y.iter_mut()
+                .zip(h.iter())
+                .zip(x.iter())
+            {
+                *y += h*x
+            }
+        }
+        y.ifft();
+
+        match method
+        {
+            ConvolutionMethod::OverlapAdd => {
+                self.output.extend(y[..p].iter()
+                    .zip(self.overlap.iter())
+                    .map(|(y, &o)| y.re + o)
+                );
+                self.overlap = y[p..].iter()
+                    .map(|y| y.re)
+                    .collect()
+            },
+            ConvolutionMethod::OverlapSave => self.output.extend(y[p..].iter()
+                .map(|y| y.re)
+            )
+        }
+    }
+}
+
+/// Streaming FFT convolution with an FIR filter, using uniformly or non-uniformly partitioned impulse responses.
+///
+/// The spectra of the partitions are computed once, and the state is kept between calls to [process](PartitionedConvolver::process),
+/// so a signal may be fed in blocks of any length. The output is delayed by the size of the first partition.
+///
+/// With non-uniform partitioning, short partitions at the start of the impulse response keep the latency low, while long partitions
+/// later on keep the cost of long impulse responses down. A partition of size P is computed once every P samples, so it must start
+/// at least P minus the first partition size samples into the impulse response.
+#[derive(Debug, Clone)]
+pub struct PartitionedConvolver<T>
+where
+    T: Float
+{
+    method: ConvolutionMethod,
+    block_size: usize,
+    stages: Vec<Stage<T>>,
+    input: Vec<T>,
+    output: VecDeque<T>
+}
+
+impl<T> PartitionedConvolver<T>
+where
+    T: Float,
+    Complex<T>: AddAssign + MulAssign + MulAssign<T>
+{
+    /// Uniformly partitioned convolver with partitions of `block_size` samples.
+    pub fn new<B>(h: &Tf<T, B, ()>, block_size: usize, method: ConvolutionMethod) -> Result<Self, PartitionedConvolverError>
+    where
+        B: List<T>
+    {
+        Self::new_non_uniform(h, &[block_size], method)
+    }
+
+    /// Non-uniformly partitioned convolver.
+    ///
+    /// Each partition size covers the next part of the impulse response, and the last partition size is repeated until the whole
+    /// impulse response is covered. The first partition size is the block size, and all other sizes must be multiples of it.
+    pub fn new_non_uniform<B>(h: &Tf<T, B, ()>, partition_sizes: &[usize], method: ConvolutionMethod) -> Result<Self, PartitionedConvolverError>
+    where
+        B: List<T>
+    {
+        let block_size = match partition_sizes.first()
+        {
+            Some(&b) if partition_sizes.iter().all(|&p| p > 0) => b,
+            _ => return Err(PartitionedConvolverError::InvalidPartitionSize)
+        };
+        if partition_sizes.iter().any(|&p| p % block_size != 0)
+        {
+            return Err(PartitionedConvolverError::PartitionNotMultipleOfBlockSize)
+        }
+
+        let h = h.b.as_view_slice();
+
+        // Group consecutive partitions of equal size into stages.
+        let mut stages = vec![];
+        let mut offset = 0;
+        let mut i = 0;
+        while offset < h.len().max(1)
+        {
+            let p = partition_sizes[i.min(partition_sizes.len() - 1)];
+            let mut end = offset;
+            while i < partition_sizes.len() && partition_sizes[i] == p
+            {
+                end += p;
+                i += 1
+            }
+            if i >= partition_sizes.len()
+            {
+                end = end.max(h.len())
+            }
+            if offset + block_size < p
+            {
+                return Err(PartitionedConvolverError::PartitionTooEarly)
+            }
+
+            // The stage outputs P samples at once, so its partitions are advanced by P minus the block size.
+            let lead = offset + block_size - p;
+            let segment: Vec<T> = core::iter::repeat(T::zero())
+                .take(lead)
+                .chain(h[offset.min(h.len())..end.min(h.len())].iter()
+                    .copied()
+                ).collect();
+            stages.push(Stage::new(&segment, p, block_size));
+
+            offset = end
+        }
+
+        let mut convolver = Self {
+            method,
+            block_size,
+            stages,
+            input: vec![],
+            output: VecDeque::new()
+        };
+        convolver.reset();
+        Ok(convolver)
+    }
+
+    /// Latency of the convolver in samples.
+    pub fn latency(&self) -> usize
+    {
+        self.block_size
+    }
+
+    /// Block convolution method. It is fixed at construction, since the state of the stages depends on it.
+    pub fn method(&self) -> ConvolutionMethod
+    {
+        self.method
+    }
+
+    /// Convolves the next samples of the signal, returning as many output samples, delayed by the [latency](PartitionedConvolver::latency).
+    pub fn process(&mut self, x: &[T]) -> Vec<T>
+    {
+        let b = self.block_size;
+        for &x in x.iter()
+        {
+            self.input.push(x);
+            if self.input.len() == b
+            {
+                let mut y = vec![T::zero(); b];
+                for stage in self.stages.iter_mut()
+                {
+                    stage.push(&self.input, self.method);
+                    for y in y.iter_mut()
+                    {
+                        *y = *y + stage.output.pop_front().unwrap()
+                    }
+                }
+                self.output.extend(y);
+                self.input.clear()
+            }
+        }
+        self.output.drain(..x.len())
+            .collect()
+    }
+
+    /// Clears the signal history, keeping the partitioned impulse response.
+    pub fn reset(&mut self)
+    {
+        let b = self.block_size;
+        for stage in self.stages.iter_mut()
+        {
+            stage.reset(b)
+        }
+        self.input.clear();
+        self.output = core::iter::repeat(T::zero())
+            .take(b)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use rand::distributions::uniform::SampleRange;
+
+    use crate::{operations::filtering::{ConvolutionMethod, PartitionedConvolver}, systems::Tf};
+
+    #[test]
+    fn test()
+    {
+        let mut rng = rand::thread_rng();
+        let h: Vec<f64> = (0..300).map(|_| (-1.0..1.0).sample_single(&mut rng))
+            .collect();
+        let x: Vec<f64> = (0..1000).map(|_| (-1.0..1.0).sample_single(&mut rng))
+            .collect();
+        let h = Tf::new(h, ());
+
+        let mut uniform = PartitionedConvolver::new(&h, 32, ConvolutionMethod::OverlapAdd)
+            .unwrap();
+        let mut non_uniform = PartitionedConvolver::new_non_uniform(&h, &[16, 16, 32, 32, 64], ConvolutionMethod::OverlapSave)
+            .unwrap();
+
+        // Fed in uneven blocks, both match direct convolution delayed by the latency.
+        let mut y1 = vec![];
+        let mut y2 = vec![];
+        for x in x.chunks(37)
+        {
+            y1.append(&mut uniform.process(x));
+            y2.append(&mut non_uniform.process(x));
+        }
+        for n in 0..x.len()
+        {
+            let y = |d: usize| (0..h.b.len()).filter(|&k| k + d <= n)
+                .map(|k| h.b[k]*x[n - d - k])
+                .sum::<f64>();
+            assert!((y1[n] - y(uniform.latency())).abs() < 1e-9);
+            assert!((y2[n] - y(non_uniform.latency())).abs() < 1e-9);
+        }
+    }
+}
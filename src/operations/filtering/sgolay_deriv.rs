@@ -0,0 +1,205 @@
+use core::ops::Mul;
+
+use num::{Float, Zero};
+
+use crate::{gen::filter::SGolay, operations::filtering::SGolayFiltError, quantities::{List, Lists}, systems::Tf};
+
+/// Handling of the signal edges, where the Savitzky-Golay window extends beyond the signal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SGolayEdge<T>
+{
+    /// The polynomial fitted to the first or last window is evaluated at the edge samples.
+    Interp,
+    /// The signal is reflected about its edge samples, i.e. `d c b | a b c d | c b a`.
+    Mirror,
+    /// The signal is extended with its edge samples.
+    Nearest,
+    /// The signal is extended periodically.
+    Wrap,
+    /// The signal is extended with a constant value.
+    Constant(T)
+}
+
+impl<T> Default for SGolayEdge<T>
+{
+    fn default() -> Self
+    {
+        Self::Interp
+    }
+}
+
+impl<T> SGolayEdge<T>
+where
+    T: Copy
+{
+    /// Index into a signal of length `n` of the extended signal at index `i`, which may lie outside the signal, or none if
+    /// the extended signal is a constant there.
+    pub(crate) fn extend_index(&self, n: usize, i: isize) -> Option<usize>
+    {
+        let n = n as isize;
+        if (0..n).contains(&i)
+        {
+            return Some(i as usize)
+        }
+        match *self
+        {
+            SGolayEdge::Mirror => {
+                if n == 1
+                {
+                    return Some(0)
+                }
+                let p = 2*(n - 1);
+                let i = i.rem_euclid(p);
+                Some((if i >= n {p - i} else {i}) as usize)
+            },
+            SGolayEdge::Interp | SGolayEdge::Nearest => Some(i.clamp(0, n - 1) as usize),
+            SGolayEdge::Wrap => Some(i.rem_euclid(n) as usize),
+            SGolayEdge::Constant(_) => None
+        }
+    }
+
+    /// Value of the extended signal at index `i`, which may lie outside the signal.
+    pub(crate) fn extend(&self, x: &[T], i: isize) -> T
+    {
+        match (self.extend_index(x.len(), i), *self)
+        {
+            (Some(i), _) => x[i],
+            (None, SGolayEdge::Constant(c)) => c,
+            (None, _) => unreachable!()
+        }
+    }
+}
+
+/// Applies a set of `n` Savitzky-Golay filters, as made by [sgolay](crate::gen::filter::SGolay::sgolay), to a signal.
+///
+/// Every filter is correlated with a window of `n` samples, and the middle filter is the one centered on its output sample. With
+/// [SGolayEdge::Interp], the off-center filters are applied to the first and last windows to give the edge samples. Otherwise,
+/// the middle filter is applied to the extended signal throughout.
+pub(crate) fn sgolay_apply<T, X, Y>(h: &[&[T]], x: &[X], edge: SGolayEdge<X>) -> Result<Vec<Y>, SGolayFiltError>
+where
+    T: Copy + Into<Y>,
+    X: Copy + Into<Y>,
+    Y: Zero + Mul<Output = Y> + Copy
+{
+    let n = h.len();
+    if n == 0 || h.iter().any(|h| h.len() > n)
+    {
+        return Err(SGolayFiltError::NotSGolay)
+    }
+    let k = n/2;
+    let len = x.len();
+    let interp = matches!(edge, SGolayEdge::Interp);
+    if interp && len < n
+    {
+        return Err(SGolayFiltError::InsufficientData)
+    }
+
+    let dot = |b: &[T], x: &mut dyn Iterator<Item = X>| b.iter()
+        .zip(x)
+        .fold(Y::zero(), |y, (&b, x)| y + b.into()*x.into());
+
+    Ok((0..len).map(|i| {
+        if interp && i < k
+        {
+            dot(h[i], &mut x[..n].iter().copied())
+        }
+        else if interp && i + k >= len
+        {
+            dot(h[i + n - len], &mut x[len - n..].iter().copied())
+        }
+        else
+        {
+            dot(h[k], &mut (0..n).map(|j| edge.extend(x, i as isize + j as isize - k as isize)))
+        }
+    }).collect())
+}
+
+/// Savitzky-Golay smoothing or differentiation.
+///
+/// Fits a polynomial of the given order to each window of `numtaps` samples, and evaluates its derivative of the given order at
+/// the center of the window. Derivatives are scaled by the sample spacing, so that they are with respect to time rather than
+/// sample index.
+pub trait SGolayDeriv<T>: Lists<T>
+where
+    T: Float
+{
+    fn sgolay_deriv(
+        self,
+        order: usize,
+        numtaps: usize,
+        derivative: usize,
+        spacing: T,
+        edge: SGolayEdge<T>
+    ) -> Result<Self::RowsMapped<Vec<T>>, SGolayFiltError>;
+}
+
+impl<T, L> SGolayDeriv<T> for L
+where
+    T: Float,
+    L: Lists<T, RowOwned: List<T>>,
+    Tf<T, Vec<T>>: SGolay<Vec<Tf<T, Vec<T>>>, usize>
+{
+    fn sgolay_deriv(
+        self,
+        order: usize,
+        numtaps: usize,
+        derivative: usize,
+        spacing: T,
+        edge: SGolayEdge<T>
+    ) -> Result<Self::RowsMapped<Vec<T>>, SGolayFiltError>
+    {
+        if !(spacing > T::zero()) || !spacing.is_finite()
+        {
+            return Err(SGolayFiltError::InvalidSpacing)
+        }
+
+        let h: Vec<Tf<T, Vec<T>>> = Tf::sgolay(order, numtaps, derivative, spacing)
+            .map_err(SGolayFiltError::SGolay)?;
+        let h: Vec<&[T]> = h.iter()
+            .map(|h| h.b.as_view_slice())
+            .collect();
+
+        self.try_map_rows_into_owned(|x| sgolay_apply(&h, x.as_view_slice(), edge))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use crate::operations::filtering::{SGolayDeriv, SGolayEdge};
+
+    #[test]
+    fn test()
+    {
+        const DT: f64 = 0.01;
+
+        // Derivatives of a polynomial within the fitting order are exact, also at the edges.
+        let x: Vec<f64> = (0..100).map(|i| {
+                let t = i as f64*DT;
+                t*t*t - 2.0*t
+            }).collect();
+        let dx = x.clone()
+            .sgolay_deriv(3, 11, 1, DT, SGolayEdge::Interp)
+            .unwrap();
+        let ddx = x.clone()
+            .sgolay_deriv(3, 11, 2, DT, SGolayEdge::Interp)
+            .unwrap();
+
+        for (i, (dx, ddx)) in dx.into_iter()
+            .zip(ddx)
+            .enumerate()
+        {
+            let t = i as f64*DT;
+            assert!((dx - (3.0*t*t - 2.0)).abs() < 1e-6);
+            assert!((ddx - 6.0*t).abs() < 1e-6);
+        }
+
+        for edge in [SGolayEdge::Mirror, SGolayEdge::Nearest, SGolayEdge::Wrap, SGolayEdge::Constant(0.0)]
+        {
+            let y = x.clone()
+                .sgolay_deriv(3, 11, 0, DT, edge)
+                .unwrap();
+            assert!((y[50] - x[50]).abs() < 1e-9);
+        }
+    }
+}
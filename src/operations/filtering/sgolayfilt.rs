@@ -1,12 +1,7 @@
-use std::ops::Deref;
-
-use ndarray::{Array1, Array2};
 use num::complex::ComplexFloat;
 use thiserror::Error;
 
-use crate::{util::{ComplexOp, MaybeLenEq}, quantities::{OwnedListOrSingle, ContainerOrSingle, List, ListOrSingle, Lists, MaybeContainer, OwnedList}, operations::filtering::Filter, System, systems::Tf};
-
-//FIXME: Discontinuity at len - k.
+use crate::{gen::filter::SGolayError, util::{ComplexOp, MaybeLenEq}, quantities::{ContainerOrSingle, List, ListOrSingle, Lists, OwnedList}, operations::filtering::{sgolay_apply, SGolayEdge}, systems::Tf};
 
 #[derive(Debug, Clone, Copy, PartialEq, Error)]
 pub enum SGolayFiltError
@@ -14,7 +9,11 @@ pub enum SGolayFiltError
     #[error("Not a Savitzsky-Golay filter set. Filter count should equal filter length.")]
     NotSGolay,
     #[error("Insufficient data for filter. Data sequence length must be larger or equal to filter length.")]
-    InsufficientData
+    InsufficientData,
+    #[error("Sample spacing must be a positive number.")]
+    InvalidSpacing,
+    #[error("Filter design failed: {0}")]
+    SGolay(SGolayError)
 }
 
 pub trait SGolayFilt<T, B, X, XX>: List<Tf<T, B>>
@@ -24,78 +23,35 @@ where
     B: List<T> + MaybeLenEq<Self, true>,
     XX: Lists<X>
 {
-    fn sgolayfilt(self, x: XX) -> Result<XX::Mapped<<T as ComplexOp<X>>::Output>, SGolayFiltError>;
+    /// Applies a set of Savitzky-Golay filters to each row.
+    ///
+    /// The filter set made by [sgolay](crate::gen::filter::SGolay::sgolay) gives derivative estimates, scaled by the sample
+    /// spacing, if a derivative order and spacing were given.
+    fn sgolayfilt(self, x: XX, edge: SGolayEdge<X>) -> Result<XX::Mapped<<T as ComplexOp<X>>::Output>, SGolayFiltError>;
 }
 
 impl<T, B, L, X, XX, Y> SGolayFilt<T, B, X, XX> for L
 where
     L: List<Tf<T, B>>,
     X: ComplexFloat + Into<Y>,
-    T: ComplexFloat<Real = X::Real> + ComplexOp<X, Output = Y>,
-    B: List<T, Owned: OwnedList<T>> + MaybeLenEq<Self, true> + Clone,
+    T: ComplexFloat<Real = X::Real> + ComplexOp<X, Output = Y> + Into<Y>,
+    B: List<T> + MaybeLenEq<Self, true>,
     XX: Lists<X, RowOwned: OwnedList<X>>,
     <XX::RowOwned as ContainerOrSingle<X>>::Mapped<Y>: OwnedList<Y>,
-    Y: ComplexFloat + Clone + 'static,
-    XX::RowsMapped<<XX::RowOwned as ContainerOrSingle<X>>::Mapped<Y>>: Into<XX::Mapped<Y>>,
-    for<'a> <B::Owned as MaybeContainer<T>>::View<'a>: List<T>,
-    for<'a> Tf<T, <B::Owned as MaybeContainer<T>>::View<'a>>: Filter<X, XX::RowOwned, Output = <XX::RowOwned as ContainerOrSingle<X>>::Mapped<Y>> + System<Set = T>
+    Y: ComplexFloat,
+    XX::RowsMapped<<XX::RowOwned as ContainerOrSingle<X>>::Mapped<Y>>: Into<XX::Mapped<Y>>
 {
-    fn sgolayfilt(self, x: XX) -> Result<XX::Mapped<Y>, SGolayFiltError>
+    fn sgolayfilt(self, x: XX, edge: SGolayEdge<X>) -> Result<XX::Mapped<Y>, SGolayFiltError>
     {
-        let mut h: Vec<_> = self.into_vec()
-            .into_iter()
-            .map(|tf| tf.into_owned())
+        let h = self.into_vec();
+        let h: Vec<&[T]> = h.iter()
+            .map(|tf| tf.b.as_view_slice())
             .collect();
-        let n = h.len();
-
-        for tf in h.iter()
-        {
-            if tf.b.as_view_slice().len() > n
-            {
-                return Err(SGolayFiltError::NotSGolay)
-            }
-        }
-
-        let k = n/2;
-        h[k].b.as_mut_slice()
-            .reverse();
 
         Ok(x.try_map_rows_into_owned(|x| {
-            let len = x.as_view_slice()
-                .len();
-            if len < n
-            {
-                return Err(SGolayFiltError::InsufficientData)
-            }
-
-            let x1 = Array1::<Y>::from_shape_fn(n, |i| x.as_view_slice()[i].into());
-            let x2 = Array1::<Y>::from_shape_fn(n, |i| x.as_view_slice()[len + i - n].into());
-
-            let mut y = h[k].as_view()
-                .filter(x, ());
-
-            y.as_mut_slice()
-                .rotate_left(n - k);
-
-            let h1 = Array2::<Y>::from_shape_fn((k, n), |(i, j)| h[i].b.deref().as_view_slice()[j].into());
-            let h2 = Array2::<Y>::from_shape_fn((n - k - 1, n), |(i, j)| h[i + k + 1].b.deref().as_view_slice()[j].into());
-
-            let y1 = h1.dot(&x1);
-            let y2 = h2.dot(&x2);
-
-            for (y, y1) in y.as_mut_slice()[..k]
-                .iter_mut()
-                .zip(y1.into_iter())
-            {
-                *y = y1
-            }
-            for (y, y2) in y.as_mut_slice()[len + 1 + k - n..]
-                .iter_mut()
-                .zip(y2.into_iter())
-            {
-                *y = y2
-            }
-            Ok(y)
+            let mut y = sgolay_apply(&h, x.as_view_slice(), edge)?
+                .into_iter();
+            Ok(x.map_into_owned(|_| y.next().unwrap()))
         })?.into())
     }
 }
@@ -108,7 +64,7 @@ mod test
     use array_math::ArrayOps;
     use rand::distributions::uniform::SampleRange;
 
-    use crate::{plot, gen::filter::SGolay, operations::filtering::SGolayFilt, systems::Tf};
+    use crate::{plot, gen::filter::SGolay, operations::filtering::{SGolayDeriv, SGolayEdge, SGolayFilt}, systems::Tf};
 
     #[test]
     fn test()
@@ -125,10 +81,28 @@ mod test
         let mut rng = rand::thread_rng();
         let x = t.map(|t| (TAU*F*t).cos() + (-1.0..1.0).sample_single(&mut rng));
 
-        let y = SGolayFilt::sgolayfilt(h, x)
+        let y = SGolayFilt::sgolayfilt(h, x, SGolayEdge::Interp)
             .unwrap();
 
         plot::plot_curves("x(t), y(t)", "plots/xy_t_sgolayfilt.png", [&t.zip(x), &t.zip(y)])
             .unwrap();
+
+        // A derivative filter set gives the same estimates as sgolay_deriv, for every edge mode.
+        const M_D: usize = 11;
+        let h: [Tf::<_, [f64; M_D]>; M_D] = Tf::sgolay(3, (), 1, 1.0/FS)
+            .unwrap();
+        for edge in [SGolayEdge::Interp, SGolayEdge::Mirror, SGolayEdge::Nearest, SGolayEdge::Wrap, SGolayEdge::Constant(0.0)]
+        {
+            let dy = SGolayFilt::sgolayfilt(h.clone(), x, edge)
+                .unwrap();
+            let dy_deriv = x.to_vec()
+                .sgolay_deriv(3, M_D, 1, 1.0/FS, edge)
+                .unwrap();
+            for (dy, dy_deriv) in dy.into_iter()
+                .zip(dy_deriv)
+            {
+                assert!((dy - dy_deriv).abs() < 1e-9*dy_deriv.abs().max(1.0));
+            }
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,130 @@
+use ndarray::Array2;
+use ndarray_linalg::Lapack;
+use num::{Float, NumCast};
+
+use crate::{gen::filter::SGolayError, operations::filtering::{SGolayEdge, SGolayFiltError}, util};
+
+/// 2-D Savitzky-Golay smoothing.
+///
+/// Fits a 2-D polynomial of the given total order to each window of `numtaps` samples (rows by columns), and evaluates it at the
+/// center of the window.
+pub trait SGolayFilt2d<T>: Sized
+where
+    T: Float
+{
+    fn sgolayfilt_2d(self, order: usize, numtaps: [usize; 2], edge: SGolayEdge<T>) -> Result<Array2<T>, SGolayFiltError>;
+}
+
+impl<T> SGolayFilt2d<T> for Array2<T>
+where
+    T: Float + Lapack<Real = T>
+{
+    fn sgolayfilt_2d(self, order: usize, numtaps: [usize; 2], edge: SGolayEdge<T>) -> Result<Array2<T>, SGolayFiltError>
+    {
+        let [n1, n2] = numtaps;
+        if n1 % 2 != 1 || n2 % 2 != 1
+        {
+            return Err(SGolayFiltError::SGolay(SGolayError::EvenFilterLength))
+        }
+        let terms: Vec<(usize, usize)> = (0..=order).flat_map(|p| (0..=order - p).map(move |q| (p, q)))
+            .collect();
+        if terms.len() > n1*n2
+        {
+            return Err(SGolayFiltError::SGolay(SGolayError::OrderOutOfRange))
+        }
+        let (k1, k2) = (n1/2, n2/2);
+        let (m1, m2) = self.dim();
+        if edge == SGolayEdge::Interp && (m1 < n1 || m2 < n2)
+        {
+            return Err(SGolayFiltError::InsufficientData)
+        }
+
+        // Monomials at offsets relative to the window center.
+        let monomial = |a: isize, b: isize, (p, q): (usize, usize)| {
+            let a = <T as NumCast>::from(a).unwrap();
+            let b = <T as NumCast>::from(b).unwrap();
+            Float::powi(a, p as i32)*Float::powi(b, q as i32)
+        };
+        let c = Array2::from_shape_fn((n1*n2, terms.len()), |(i, t)| monomial((i/n2) as isize - k1 as isize, (i % n2) as isize - k2 as isize, terms[t]));
+        let g = util::pinv(c);
+
+        // Weights of each window sample, for evaluation at a given offset from the window center.
+        let kernel = |a: isize, b: isize| -> Vec<T> {
+            (0..n1*n2).map(|i| terms.iter()
+                    .enumerate()
+                    .fold(T::zero(), |s, (t, &pq)| s + monomial(a, b, pq)*g[(t, i)])
+                ).collect()
+        };
+        let center = kernel(0, 0);
+        let mut edge_kernels: Vec<Option<Vec<T>>> = vec![None; n1*n2];
+
+        let mut y = Array2::from_elem((m1, m2), T::zero());
+        for ((i, j), y) in y.indexed_iter_mut()
+        {
+            *y = if edge == SGolayEdge::Interp
+            {
+                let r0 = i.saturating_sub(k1).min(m1 - n1);
+                let c0 = j.saturating_sub(k2).min(m2 - n2);
+                let a = i as isize - (r0 + k1) as isize;
+                let b = j as isize - (c0 + k2) as isize;
+                let h = if a == 0 && b == 0
+                {
+                    &center
+                }
+                else
+                {
+                    &*edge_kernels[((a + k1 as isize) as usize)*n2 + (b + k2 as isize) as usize].get_or_insert_with(|| kernel(a, b))
+                };
+                h.iter()
+                    .enumerate()
+                    .fold(T::zero(), |s, (w, &h)| s + h*self[(r0 + w/n2, c0 + w % n2)])
+            }
+            else
+            {
+                center.iter()
+                    .enumerate()
+                    .fold(T::zero(), |s, (w, &h)| {
+                        let r = edge.extend_index(m1, (i + w/n2) as isize - k1 as isize);
+                        let c = edge.extend_index(m2, (j + w % n2) as isize - k2 as isize);
+                        let x = match (r, c, edge)
+                        {
+                            (Some(r), Some(c), _) => self[(r, c)],
+                            (_, _, SGolayEdge::Constant(x)) => x,
+                            _ => unreachable!()
+                        };
+                        s + h*x
+                    })
+            }
+        }
+
+        Ok(y)
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use ndarray::Array2;
+
+    use crate::operations::filtering::{SGolayEdge, SGolayFilt2d};
+
+    #[test]
+    fn test()
+    {
+        // Quadratic surfaces are preserved by a second order fit, also at the edges.
+        let x = Array2::from_shape_fn((20, 30), |(i, j)| {
+            let (i, j) = (i as f64, j as f64);
+            0.1*i*i - 0.2*i*j + 0.05*j*j + i - 3.0
+        });
+
+        let y = x.clone()
+            .sgolayfilt_2d(2, [5, 7], SGolayEdge::Interp)
+            .unwrap();
+
+        for (x, y) in x.iter()
+            .zip(y.iter())
+        {
+            assert!((x - y).abs() < 1e-6)
+        }
+    }
+}
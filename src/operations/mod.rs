@@ -6,8 +6,11 @@ moddef::moddef!(
     },
     flat(pub) mod {
         decode,
+        detrend,
         encode,
+        quantize,
         simplify,
+        unwrap,
         window
     }
 );
\ No newline at end of file
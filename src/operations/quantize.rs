@@ -0,0 +1,192 @@
+use num::{Float, NumCast};
+
+use crate::{operations::EncodeOverflow, quantities::Lists};
+
+/// Rounding of values between two quantization steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuantizeRounding
+{
+    /// Round to nearest, with ties away from zero.
+    #[default]
+    Nearest,
+    /// Round to nearest, with ties to even.
+    Convergent,
+    /// Round towards negative infinity, i.e. two's complement truncation.
+    Floor,
+    /// Round towards positive infinity.
+    Ceil,
+    /// Round towards zero.
+    Zero
+}
+
+impl QuantizeRounding
+{
+    pub fn round<T>(self, x: T) -> T
+    where
+        T: Float
+    {
+        match self
+        {
+            QuantizeRounding::Nearest => x.round(),
+            QuantizeRounding::Convergent => {
+                let r = x.round();
+                let two = T::one() + T::one();
+                if (r - x).abs() == two.recip() && r % two != T::zero()
+                {
+                    r - x.signum()
+                }
+                else
+                {
+                    r
+                }
+            },
+            QuantizeRounding::Floor => x.floor(),
+            QuantizeRounding::Ceil => x.ceil(),
+            QuantizeRounding::Zero => x.trunc()
+        }
+    }
+}
+
+/// Signed two's complement fixed-point format, with `word_length` bits in total of which `fraction_length` are fractional.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedPointFormat
+{
+    pub word_length: u32,
+    pub fraction_length: u32
+}
+
+impl FixedPointFormat
+{
+    pub const Q15: Self = Self::q(0, 15);
+    pub const Q31: Self = Self::q(0, 31);
+
+    /// The Qm.n format, with `m` integer bits and `n` fractional bits besides the sign bit.
+    pub const fn q(m: u32, n: u32) -> Self
+    {
+        Self {
+            word_length: m + n + 1,
+            fraction_length: n
+        }
+    }
+
+    /// Value of the least significant bit.
+    pub fn lsb<T>(&self) -> T
+    where
+        T: Float
+    {
+        (T::one() + T::one()).powi(-(self.fraction_length as i32))
+    }
+
+    /// Number of bits of the integer representation, limited to those of an `i128`.
+    fn bits(&self) -> u32
+    {
+        self.word_length.clamp(1, i128::BITS)
+    }
+
+    /// Smallest and largest representable integer.
+    ///
+    /// Word lengths beyond 128 bits are limited to the range of an `i128`.
+    pub fn integer_range(&self) -> (i128, i128)
+    {
+        let bits = self.bits();
+        if bits == i128::BITS
+        {
+            return (i128::MIN, i128::MAX)
+        }
+        let half = 1i128 << (bits - 1);
+        (-half, half - 1)
+    }
+
+    /// Brings an integer into the representable range, by saturation or two's complement wrap-around.
+    pub fn overflow(&self, x: i128, overflow: EncodeOverflow) -> i128
+    {
+        let (min, max) = self.integer_range();
+        match overflow
+        {
+            EncodeOverflow::Saturate => x.clamp(min, max),
+            // Sign-extends the lowest bits, which cannot overflow at any word length.
+            EncodeOverflow::Wrap => {
+                let shift = i128::BITS - self.bits();
+                (x << shift) >> shift
+            }
+        }
+    }
+
+    /// Quantizes a value to its integer representation.
+    pub fn to_integer<T>(&self, x: T, rounding: QuantizeRounding, overflow: EncodeOverflow) -> i128
+    where
+        T: Float
+    {
+        let (min, max) = self.integer_range();
+        let y = rounding.round(x/self.lsb::<T>());
+        let y = <i128 as NumCast>::from(y)
+            .unwrap_or(if y > T::zero() {max} else if y < T::zero() {min} else {0});
+        self.overflow(y, overflow)
+    }
+
+    /// Value of an integer representation.
+    pub fn to_float<T>(&self, x: i128) -> T
+    where
+        T: Float
+    {
+        <T as NumCast>::from(x).unwrap()*self.lsb::<T>()
+    }
+
+    /// Quantizes a value, giving the nearest representable value according to the rounding and overflow modes.
+    pub fn quantize<T>(&self, x: T, rounding: QuantizeRounding, overflow: EncodeOverflow) -> T
+    where
+        T: Float
+    {
+        self.to_float(self.to_integer(x, rounding, overflow))
+    }
+}
+
+pub trait Quantize<T>: Lists<T>
+where
+    T: Float
+{
+    fn quantize(self, format: FixedPointFormat, rounding: QuantizeRounding, overflow: EncodeOverflow) -> Self::Mapped<T>;
+}
+
+impl<T, L> Quantize<T> for L
+where
+    T: Float,
+    L: Lists<T>
+{
+    fn quantize(self, format: FixedPointFormat, rounding: QuantizeRounding, overflow: EncodeOverflow) -> Self::Mapped<T>
+    {
+        self.map_into_owned(|x| format.quantize(x, rounding, overflow))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use crate::operations::{EncodeOverflow, FixedPointFormat, Quantize, QuantizeRounding};
+
+    #[test]
+    fn test()
+    {
+        let q = FixedPointFormat::q(1, 2);
+
+        let x = [0.3, -0.375, 1.9, -2.6, 2.0];
+        assert_eq!(x.quantize(q, QuantizeRounding::Nearest, EncodeOverflow::Saturate), [0.25, -0.5, 1.75, -2.0, 1.75]);
+        assert_eq!(x.quantize(q, QuantizeRounding::Convergent, EncodeOverflow::Saturate), [0.25, -0.5, 1.75, -2.0, 1.75]);
+        assert_eq!(x.quantize(q, QuantizeRounding::Floor, EncodeOverflow::Wrap), [0.25, -0.5, 1.75, 1.25, -2.0]);
+
+        // The widest formats stay within the range of an i128.
+        for q in [FixedPointFormat::q(0, 126), FixedPointFormat::q(1, 126), FixedPointFormat::q(200, 0)]
+        {
+            let (min, max) = q.integer_range();
+            assert_eq!(min, -max - 1);
+            assert_eq!(q.overflow(max, EncodeOverflow::Wrap), max);
+            assert_eq!(q.overflow(min, EncodeOverflow::Wrap), min);
+            assert_eq!(q.overflow(i128::MAX, EncodeOverflow::Saturate), max);
+            if q.fraction_length == 126
+            {
+                assert_eq!(x.quantize(q, QuantizeRounding::Nearest, EncodeOverflow::Wrap)[..2], [0.3, -0.375]);
+            }
+        }
+        assert_eq!(FixedPointFormat::q(0, 126).overflow(i128::MAX, EncodeOverflow::Wrap), -1);
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::VecDeque;
+
+use num::{traits::FloatConst, Float, NumCast};
+use option_trait::Maybe;
+use thiserror::Error;
+
+use crate::{gen::window::{WindowGen, WindowRange}, windows::Kaiser};
+
+/// Number of kernel samples stored per zero crossing. The kernel is interpolated linearly in between.
+const OVERSAMPLING: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum AsyncResamplerError
+{
+    #[error("Resampling ratio must be a positive finite number.")]
+    InvalidRatio,
+    #[error("Cutoff must be between zero and one, exclusive of zero.")]
+    InvalidCutoff,
+    #[error("Kernel must have at least one zero crossing on each side.")]
+    ZeroCrossingsIsZero
+}
+
+/// Asynchronous sample rate converter for arbitrary, and slowly time-varying, resampling ratios.
+///
+/// Every output sample is interpolated at its exact position between the input samples using a windowed sinc kernel, with a Kaiser
+/// window. When downsampling, the kernel is stretched to low-pass filter at the output Nyquist frequency. The ratio may be changed
+/// between calls to [process](AsyncResampler::process) to track drift between independent clocks, and the output continues
+/// seamlessly across calls.
+///
+/// Output samples are aligned with the input in time, but each of them can only be computed once the input has reached the end
+/// of the kernel, i.e. after a [latency](AsyncResampler::latency) of a few input samples.
+#[derive(Debug, Clone)]
+pub struct AsyncResampler<T>
+where
+    T: Float
+{
+    ratio: T,
+    cutoff: T,
+    zero_crossings: usize,
+    /// Right half of the windowed sinc kernel, sampled at `OVERSAMPLING` points per zero crossing.
+    kernel: Vec<T>,
+    history: VecDeque<T>,
+    /// Position of the next output sample, in input samples relative to the oldest kept input sample.
+    time: T
+}
+
+impl<T> AsyncResampler<T>
+where
+    T: Float + FloatConst
+{
+    /// Creates a resampler from the ratio of the output to the input sampling frequency.
+    ///
+    /// The kernel has `zero_crossings` zero crossings on each side (default 16), a Kaiser window of parameter `beta` (default 8),
+    /// and its cutoff frequency is `cutoff` times the lower Nyquist frequency (default 0.95).
+    pub fn new<N, B, W>(ratio: T, zero_crossings: N, beta: B, cutoff: W) -> Result<Self, AsyncResamplerError>
+    where
+        N: Maybe<usize>,
+        B: Maybe<T>,
+        W: Maybe<T>
+    {
+        let zero_crossings = zero_crossings.into_option()
+            .unwrap_or(16);
+        let beta = beta.into_option()
+            .unwrap_or_else(|| <T as NumCast>::from(8.0).unwrap());
+        let cutoff = cutoff.into_option()
+            .unwrap_or_else(|| <T as NumCast>::from(0.95).unwrap());
+
+        if zero_crossings == 0
+        {
+            return Err(AsyncResamplerError::ZeroCrossingsIsZero)
+        }
+        if !(cutoff > T::zero() && cutoff <= T::one())
+        {
+            return Err(AsyncResamplerError::InvalidCutoff)
+        }
+
+        let n = zero_crossings*OVERSAMPLING;
+        let l = <T as NumCast>::from(OVERSAMPLING).unwrap();
+        let window: Vec<T> = Kaiser {beta}
+            .window_gen(2*n + 1, WindowRange::Symmetric);
+        let kernel = window[n..].iter()
+            .enumerate()
+            .map(|(i, &w)| {
+                let v = T::PI()*<T as NumCast>::from(i).unwrap()/l;
+                if v.is_zero() {w} else {w*v.sin()/v}
+            }).chain([T::zero()])
+            .collect();
+
+        let mut resampler = Self {
+            ratio: T::one(),
+            cutoff,
+            zero_crossings,
+            kernel,
+            history: VecDeque::new(),
+            time: T::zero()
+        };
+        resampler.set_ratio(ratio)?;
+        Ok(resampler)
+    }
+
+    pub fn ratio(&self) -> T
+    {
+        self.ratio
+    }
+
+    /// Changes the ratio of the output to the input sampling frequency, from the next output sample on.
+    pub fn set_ratio(&mut self, ratio: T) -> Result<(), AsyncResamplerError>
+    {
+        if !(ratio > T::zero() && ratio.is_finite())
+        {
+            return Err(AsyncResamplerError::InvalidRatio)
+        }
+        self.ratio = ratio;
+        Ok(())
+    }
+
+    /// Cutoff frequency of the kernel, relative to the input Nyquist frequency.
+    fn bandwidth(&self) -> T
+    {
+        self.cutoff*self.ratio.min(T::one())
+    }
+
+    /// Number of input samples an output sample has to wait for, at the current ratio.
+    pub fn latency(&self) -> T
+    {
+        <T as NumCast>::from(self.zero_crossings).unwrap()/self.bandwidth()
+    }
+
+    /// Resamples the next samples of the signal.
+    pub fn process(&mut self, x: &[T]) -> Vec<T>
+    {
+        self.history.extend(x.iter().copied());
+
+        let c = self.bandwidth();
+        let w = self.latency();
+        let step = self.ratio.recip();
+        let l = <T as NumCast>::from(OVERSAMPLING).unwrap();
+        let len = <T as NumCast>::from(self.history.len()).unwrap();
+        let max = self.kernel.len() - 1;
+
+        let mut y = vec![];
+        while (self.time + w).floor() < len
+        {
+            let t = self.time;
+            let start = <isize as NumCast>::from((t - w).ceil()).unwrap().max(0) as usize;
+            let end = <usize as NumCast>::from((t + w).floor()).unwrap();
+            let mut sum = T::zero();
+            for k in start..=end
+            {
+                let v = (c*(t - <T as NumCast>::from(k).unwrap())).abs()*l;
+                let j = <usize as NumCast>::from(v.floor()).unwrap();
+                if j < max
+                {
+                    let g = self.kernel[j] + (v - v.floor())*(self.kernel[j + 1] - self.kernel[j]);
+                    sum = sum + self.history[k]*g
+                }
+            }
+            y.push(sum*c);
+            self.time = self.time + step
+        }
+
+        // Keep some margin of past input, in case the ratio is lowered and the kernel widens.
+        let keep = <isize as NumCast>::from((self.time - w - w).floor()).unwrap();
+        if keep > 0
+        {
+            let drop = (keep as usize).min(self.history.len());
+            self.history.drain(..drop);
+            self.time = self.time - <T as NumCast>::from(drop).unwrap()
+        }
+
+        y
+    }
+
+    /// Clears the signal history, as if the stream started anew.
+    pub fn reset(&mut self)
+    {
+        self.history.clear();
+        self.time = T::zero()
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::TAU;
+
+    use crate::operations::resampling::AsyncResampler;
+
+    #[test]
+    fn test()
+    {
+        const FS: f64 = 44100.0;
+        const F: f64 = 1000.0;
+
+        let x: Vec<f64> = (0..8820).map(|i| (TAU*F*i as f64/FS).sin())
+            .collect();
+
+        // Slowly drifting clock around 48 kHz.
+        let mut resampler = AsyncResampler::new(48000.0/FS, (), (), ())
+            .unwrap();
+        let mut t = 0.0;
+        for (i, x) in x.chunks(441)
+            .enumerate()
+        {
+            let ratio = resampler.ratio();
+            for y in resampler.process(x)
+            {
+                if t > 1.0/F
+                {
+                    assert!((y - (TAU*F*t).sin()).abs() < 1e-3);
+                }
+                t += 1.0/(ratio*FS)
+            }
+            resampler.set_ratio((48000.0 + 2.0*i as f64)/FS)
+                .unwrap();
+        }
+        assert!(t > 0.19);
+    }
+}
@@ -0,0 +1,91 @@
+use core::ops::AddAssign;
+
+use num::{complex::ComplexFloat, Float};
+
+use crate::{gen::filter::{halfband_taps, HalfBandError}, quantities::{List, ListOrSingle, Lists}, util::ComplexOp, System, systems::Tf};
+
+pub trait HalfBandDecimate<X, XX>: System
+where
+    Self::Set: ComplexOp<X>,
+    X: Into<<Self::Set as ComplexOp<X>>::Output> + ComplexFloat<Real = <Self::Set as ComplexFloat>::Real>,
+    XX: Lists<X>
+{
+    fn halfband_decimate(&self, x: XX) -> Result<XX::RowsMapped<Vec<<Self::Set as ComplexOp<X>>::Output>>, HalfBandError>;
+}
+
+impl<T, B, X, XX, Y> HalfBandDecimate<X, XX> for Tf<T, B, ()>
+where
+    T: Float + ComplexOp<X, Output = Y>,
+    B: List<T>,
+    X: ComplexFloat<Real = T> + Into<Y>,
+    XX: Lists<X, RowOwned: List<X>>,
+    Y: ComplexFloat<Real = T> + AddAssign
+{
+    fn halfband_decimate(&self, x: XX) -> Result<XX::RowsMapped<Vec<Y>>, HalfBandError>
+    {
+        let (c, taps, hc) = halfband_taps(self.b.as_view_slice())?;
+        let n = 2*c;
+        let taps: Vec<(usize, Y)> = taps.into_iter()
+            .filter(|&(k, _)| k < c)
+            .map(|(k, h)| (k, h.into()))
+            .collect();
+        let hc: Y = hc.into();
+
+        Ok(x.map_rows_into_owned(|x| {
+            let x: Vec<Y> = x.into_vec()
+                .into_iter()
+                .map(Into::into)
+                .collect();
+            let get = |i: usize, k: usize| if i >= k
+            {
+                x.get(i - k)
+                    .copied()
+                    .unwrap_or_else(Y::zero)
+            }
+            else
+            {
+                Y::zero()
+            };
+
+            (0..x.len()).step_by(2)
+                .map(|i| {
+                    let mut y = hc*get(i, c);
+                    for &(k, h) in taps.iter()
+                    {
+                        y += h*(get(i, k) + get(i, n - k))
+                    }
+                    y
+                }).collect()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use array_math::{ArrayOps, SliceMath};
+    use rand::distributions::uniform::SampleRange;
+
+    use crate::{gen::filter::{HalfBand, HalfBandDesign}, operations::resampling::HalfBandDecimate, systems::Tf};
+
+    #[test]
+    fn test()
+    {
+        let h: Tf<f64, [_; 23]> = Tf::halfband((), 0.2, HalfBandDesign::Equiripple, ())
+            .unwrap();
+
+        const N: usize = 64;
+        let mut rng = rand::thread_rng();
+        let x: [f64; N] = ArrayOps::fill(|_| (-1.0..1.0).sample_single(&mut rng));
+
+        let y = h.halfband_decimate(x)
+            .unwrap();
+
+        let z: Vec<f64> = h.b.convolve_direct(&x);
+        for (y, z) in y.into_iter()
+            .zip(z.into_iter().step_by(2))
+        {
+            assert!((y - z).abs() < 1e-12)
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use core::ops::AddAssign;
+
+use num::{complex::ComplexFloat, Float};
+
+use crate::{gen::filter::{halfband_taps, HalfBandError}, quantities::{List, ListOrSingle, Lists}, util::ComplexOp, System, systems::Tf};
+
+pub trait HalfBandInterp<X, XX>: System
+where
+    Self::Set: ComplexOp<X>,
+    X: Into<<Self::Set as ComplexOp<X>>::Output> + ComplexFloat<Real = <Self::Set as ComplexFloat>::Real>,
+    XX: Lists<X>
+{
+    fn halfband_interp(&self, x: XX) -> Result<XX::RowsMapped<Vec<<Self::Set as ComplexOp<X>>::Output>>, HalfBandError>;
+}
+
+impl<T, B, X, XX, Y> HalfBandInterp<X, XX> for Tf<T, B, ()>
+where
+    T: Float + ComplexOp<X, Output = Y>,
+    B: List<T>,
+    X: ComplexFloat<Real = T> + Into<Y>,
+    XX: Lists<X, RowOwned: List<X>>,
+    Y: ComplexFloat<Real = T> + AddAssign
+{
+    fn halfband_interp(&self, x: XX) -> Result<XX::RowsMapped<Vec<Y>>, HalfBandError>
+    {
+        let (c, taps, hc) = halfband_taps(self.b.as_view_slice())?;
+        let n = 2*c;
+        let two = T::one() + T::one();
+        let taps: Vec<(usize, Y)> = taps.into_iter()
+            .filter(|&(k, _)| k < c)
+            .map(|(k, h)| (k/2, (h*two).into()))
+            .collect();
+        let hc: Y = (hc*two).into();
+        let d = (c - 1)/2;
+        let m = n/2;
+
+        Ok(x.map_rows_into_owned(|x| {
+            let x: Vec<Y> = x.into_vec()
+                .into_iter()
+                .map(Into::into)
+                .collect();
+            let get = |i: usize, k: usize| if i >= k
+            {
+                x.get(i - k)
+                    .copied()
+                    .unwrap_or_else(Y::zero)
+            }
+            else
+            {
+                Y::zero()
+            };
+
+            (0..x.len()).flat_map(|i| {
+                    let mut y = Y::zero();
+                    for &(k, h) in taps.iter()
+                    {
+                        y += h*(get(i, k) + get(i, m - k))
+                    }
+                    [y, hc*get(i, d)]
+                }).collect()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use array_math::{ArrayOps, SliceMath};
+    use rand::distributions::uniform::SampleRange;
+
+    use crate::{gen::filter::{HalfBand, HalfBandDesign}, operations::resampling::{HalfBandInterp, Upsample}, systems::Tf};
+
+    #[test]
+    fn test()
+    {
+        let h: Tf<f64, [_; 23]> = Tf::halfband((), 0.2, HalfBandDesign::Kaiser, ())
+            .unwrap();
+
+        const N: usize = 64;
+        let mut rng = rand::thread_rng();
+        let x: [f64; N] = ArrayOps::fill(|_| (-1.0..1.0).sample_single(&mut rng));
+
+        let y = h.halfband_interp(x)
+            .unwrap();
+
+        let u: [f64; 2*N] = x.upsample((), 0);
+        let z: Vec<f64> = h.b.convolve_direct(&u);
+        for (y, z) in y.into_iter()
+            .zip(z)
+        {
+            assert!((y - 2.0*z).abs() < 1e-12)
+        }
+    }
+}
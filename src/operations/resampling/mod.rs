@@ -1,9 +1,15 @@
 moddef::moddef!(
     flat(pub) mod {
+        async_resampler,
         decimate,
         downsample,
+        halfband_decimate,
+        halfband_interp,
         interp,
+        multistage,
         resample,
+        resampler,
+        upfirdn,
         upsample_fill,
         upsample
     }
@@ -0,0 +1,465 @@
+use num::{traits::FloatConst, Float, NumCast};
+use thiserror::Error;
+
+use crate::{gen::filter::{FirPm, FirPmError, FirPmType, HalfBand, HalfBandDesign, HalfBandError}, operations::resampling::UpFirDn, util::ComplexOp, System, systems::Tf};
+
+/// Largest CIC order the planner will consider.
+const MAX_CIC_ORDER: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum MultistageError
+{
+    #[error("Rate change factor must be at least 2.")]
+    FactorTooSmall,
+    #[error("Passband edge must be between 0 and 1, relative to the Nyquist frequency of the low rate.")]
+    PassbandOutOfRange,
+    #[error("Passband ripple and stopband attenuation must be positive.")]
+    InvalidTolerance,
+    #[error("Half-band design failed: {0}")]
+    HalfBand(HalfBandError),
+    #[error("Equiripple design failed: {0}")]
+    FirPm(FirPmError)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultistageDirection
+{
+    Decimation,
+    Interpolation
+}
+
+/// Kind of filter used in a stage of a [Multistage] cascade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultistageFilter
+{
+    /// Half-band equiripple filter, for stages of factor 2.
+    HalfBand,
+    /// Equiripple low-pass filter designed with the Parks-McClellan algorithm.
+    Equiripple,
+    /// Cascaded integrator-comb filter of the given order, with a differential delay of one.
+    Cic {
+        order: usize
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MultistageStage<T>
+where
+    T: Float
+{
+    pub factor: usize,
+    pub filter_type: MultistageFilter,
+    /// Impulse response of the stage, applied at its high rate. Interpolation stages include the gain of the factor.
+    pub filter: Tf<T, Vec<T>, ()>,
+    /// Estimated multiplications per sample at the high rate of the whole cascade.
+    pub cost: T
+}
+
+/// Multistage decimator or interpolator.
+///
+/// The rate change is factored into stages, and the cheapest factorization found is used, in terms of multiplications per
+/// sample at the high rate. Each stage gets an equal share of the passband ripple and the full stopband attenuation, and protects
+/// the whole band below the low rate's Nyquist frequency from aliasing. The filter orders are estimated with Kaiser's formula for
+/// equiripple filters.
+///
+/// CIC stages are only considered as the first stage of a decimator, or the last stage of an interpolator, and only if their passband
+/// droop stays within the ripple without compensation. They are applied through their equivalent FIR filter, while their cost is
+/// counted as that of the multiplier-free recursive realization.
+#[derive(Debug, Clone)]
+pub struct Multistage<T>
+where
+    T: Float
+{
+    pub direction: MultistageDirection,
+    /// Stages in the order they are applied.
+    pub stages: Vec<MultistageStage<T>>,
+    /// Estimated multiplications per sample at the high rate.
+    pub cost: T
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Plan<T>
+{
+    factor: usize,
+    filter_type: MultistageFilter,
+    order: usize,
+    /// Passband and stopband edges, relative to the Nyquist frequency at the stage's high rate.
+    edges: [T; 2],
+    cost: T
+}
+
+/// Estimated order of an equiripple low-pass filter with a transition width `df` in cycles per sample.
+fn estimate_order<T>(dp: T, ds: T, df: T) -> usize
+where
+    T: Float
+{
+    let c = |x: f64| <T as NumCast>::from(x).unwrap();
+    let n = ((-c(20.0)*(dp*ds).sqrt().log10() - c(13.0))/(c(14.6)*df)).ceil();
+    <usize as NumCast>::from(n).unwrap_or(0).max(2)
+}
+
+/// Magnitude response of a CIC decimator by `m` of order `n`, at `f` cycles per sample of its high rate.
+fn cic_response<T>(m: usize, n: usize, f: T) -> T
+where
+    T: Float + FloatConst
+{
+    let mf = <T as NumCast>::from(m).unwrap();
+    let x = T::PI()*f;
+    if x.is_zero()
+    {
+        return T::one()
+    }
+    ((mf*x).sin()/(mf*x.sin())).abs().powi(n as i32)
+}
+
+/// Cheapest stage of factor `m` at rate `rate`, relative to the high rate, with absolute passband and stopband edges.
+fn plan_stage<T>(m: usize, rate: T, passband: T, stopband: T, dp: T, ds: T, cic: bool) -> Option<Plan<T>>
+where
+    T: Float + FloatConst
+{
+    let zero = T::zero();
+    let one = T::one();
+    let two = one + one;
+    let four = two + two;
+    let c = |x: usize| <T as NumCast>::from(x).unwrap();
+
+    let edges = [passband/(rate/two), stopband/(rate/two)];
+    if !(edges[0] < edges[1]) || !(edges[0] > zero) || !(edges[1] < one)
+    {
+        return None
+    }
+    let output_rate = rate/c(m);
+
+    let order = estimate_order(dp, ds, (stopband - passband)/rate);
+    let mut best = Plan {
+        factor: m,
+        filter_type: MultistageFilter::Equiripple,
+        order,
+        edges,
+        cost: c(order/2 + 1)*output_rate
+    };
+
+    // A half-band filter needs its transition band centered on a quarter of the rate, so it is narrowed to the tighter side.
+    if m == 2
+    {
+        let tw = (one - four*passband/rate).min(four*stopband/rate - one);
+        if tw > zero
+        {
+            let order = estimate_order(dp.min(ds), dp.min(ds), tw/two);
+            let order = 4*((order + 1)/4) + 2;
+            let cost = c((order + 2)/4 + 1)*output_rate;
+            if cost < best.cost
+            {
+                best = Plan {
+                    factor: m,
+                    filter_type: MultistageFilter::HalfBand,
+                    order,
+                    edges: [(one - tw)/two, (one + tw)/two],
+                    cost
+                }
+            }
+        }
+    }
+
+    if cic
+    {
+        // The first alias band of a CIC filter starts at the stopband edge, and its attenuation only grows from there.
+        if let Some(n) = (1..=MAX_CIC_ORDER).find(|&n| cic_response(m, n, stopband/rate) <= ds)
+        {
+            if one - cic_response(m, n, passband/rate) <= dp
+            {
+                best = Plan {
+                    factor: m,
+                    filter_type: MultistageFilter::Cic {
+                        order: n
+                    },
+                    order: n*(m - 1),
+                    edges,
+                    cost: zero
+                }
+            }
+        }
+    }
+
+    Some(best)
+}
+
+/// All ordered factorizations of `n` into factors of at least 2.
+fn factorizations(n: usize) -> Vec<Vec<usize>>
+{
+    if n == 1
+    {
+        return vec![vec![]]
+    }
+    (2..=n).filter(|m| n % m == 0)
+        .flat_map(|m| factorizations(n/m)
+            .into_iter()
+            .map(move |mut f| {
+                f.insert(0, m);
+                f
+            })
+        ).collect()
+}
+
+impl<T> Multistage<T>
+where
+    T: Float + FloatConst + ComplexOp<T, Output = T>,
+    Tf<T, Vec<T>, ()>: FirPm + HalfBand<usize> + UpFirDn<T, Vec<T>> + System<Set = T>
+{
+    /// Plans and designs a decimator by `factor`.
+    ///
+    /// The passband edge is relative to the Nyquist frequency of the output. The passband ripple is peak-to-peak in dB, and
+    /// the stopband attenuation is in dB.
+    pub fn decimator(factor: usize, passband: T, ripple: T, attenuation: T) -> Result<Self, MultistageError>
+    {
+        Self::new(MultistageDirection::Decimation, factor, passband, ripple, attenuation)
+    }
+
+    /// Plans and designs an interpolator by `factor`.
+    ///
+    /// The passband edge is relative to the Nyquist frequency of the input. The passband ripple is peak-to-peak in dB, and
+    /// the stopband attenuation is in dB.
+    pub fn interpolator(factor: usize, passband: T, ripple: T, attenuation: T) -> Result<Self, MultistageError>
+    {
+        Self::new(MultistageDirection::Interpolation, factor, passband, ripple, attenuation)
+    }
+
+    fn new(direction: MultistageDirection, factor: usize, passband: T, ripple: T, attenuation: T) -> Result<Self, MultistageError>
+    {
+        let zero = T::zero();
+        let one = T::one();
+        let two = one + one;
+        let twenty = <T as NumCast>::from(20.0).unwrap();
+        let c = |x: usize| <T as NumCast>::from(x).unwrap();
+
+        if factor < 2
+        {
+            return Err(MultistageError::FactorTooSmall)
+        }
+        if !(passband > zero && passband < one)
+        {
+            return Err(MultistageError::PassbandOutOfRange)
+        }
+        if !(ripple > zero) || !(attenuation > zero)
+        {
+            return Err(MultistageError::InvalidTolerance)
+        }
+        let g = Float::powf(c(10), ripple/twenty);
+        let dp = (g - one)/(g + one);
+        let ds = Float::powf(c(10), -attenuation/twenty);
+
+        // Everything below the low rate's Nyquist frequency must be free of aliasing.
+        let low_rate = Float::recip(c(factor));
+        let passband = passband*low_rate/two;
+        let protected = low_rate/two;
+
+        let best = factorizations(factor).into_iter()
+            .filter_map(|factors| {
+                let dp = dp/c(factors.len());
+                let mut rate = one;
+                let mut plans = vec![];
+                for (i, &m) in factors.iter()
+                    .enumerate()
+                {
+                    let output_rate = rate/c(m);
+                    let stopband = if i + 1 == factors.len() {protected} else {output_rate - protected};
+                    plans.push(plan_stage(m, rate, passband, stopband, dp, ds, i == 0)?);
+                    rate = output_rate
+                }
+                Some(plans)
+            }).map(|plans| {
+                let cost = plans.iter()
+                    .map(|plan| plan.cost)
+                    .fold(zero, |a, b| a + b);
+                (cost, plans)
+            }).min_by(|(a, pa), (b, pb)| a.partial_cmp(b)
+                .unwrap()
+                .then(pa.len().cmp(&pb.len()))
+            );
+        let (cost, plans) = match best
+        {
+            Some(best) => best,
+            None => return Err(MultistageError::PassbandOutOfRange)
+        };
+
+        let dp = dp/c(plans.len());
+        let mut stages = plans.into_iter()
+            .map(|plan| {
+                let Plan { factor, filter_type, order, edges: [fp, fs], cost } = plan;
+                let mut b = match filter_type
+                {
+                    MultistageFilter::Equiripple => {
+                        let (h, _, ()) = Tf::<T, Vec<T>, ()>::firpm(
+                            order,
+                            [zero, fp, fs, one],
+                            [one, one, zero, zero],
+                            [one, dp/ds],
+                            FirPmType::Symmetric,
+                            (),
+                            c(3),
+                            c(3),
+                            c(3),
+                            c(3)
+                        ).map_err(MultistageError::FirPm)?;
+                        h.b.into_inner()
+                    },
+                    MultistageFilter::HalfBand => Tf::<T, Vec<T>, ()>::halfband(order, fs - fp, HalfBandDesign::Equiripple, ())
+                        .map_err(MultistageError::HalfBand)?
+                        .b
+                        .into_inner(),
+                    MultistageFilter::Cic { order } => {
+                        let mut b = vec![one];
+                        for _ in 0..order
+                        {
+                            b = (0..b.len() + factor - 1).map(|k| b[k.saturating_sub(factor - 1)..=k.min(b.len() - 1)].iter()
+                                    .fold(zero, |a, &b| a + b)/c(factor)
+                                ).collect()
+                        }
+                        b
+                    }
+                };
+                if direction == MultistageDirection::Interpolation
+                {
+                    for b in b.iter_mut()
+                    {
+                        *b = *b*c(factor)
+                    }
+                }
+                Ok(MultistageStage {
+                    factor,
+                    filter_type,
+                    filter: Tf::new(b, ()),
+                    cost
+                })
+            }).collect::<Result<Vec<_>, _>>()?;
+
+        // An interpolator is the transpose of the decimator, so the stage at the lowest rate comes first.
+        if direction == MultistageDirection::Interpolation
+        {
+            stages.reverse()
+        }
+
+        Ok(Self {
+            direction,
+            stages,
+            cost
+        })
+    }
+
+    /// Group delay of the cascade, in samples at the high rate.
+    pub fn delay(&self) -> T
+    {
+        let two = T::one() + T::one();
+        let c = |x: usize| <T as NumCast>::from(x).unwrap();
+        let mut scale = match self.direction
+        {
+            MultistageDirection::Decimation => 1,
+            MultistageDirection::Interpolation => self.stages.iter()
+                .map(|stage| stage.factor)
+                .product()
+        };
+        let mut delay = T::zero();
+        for stage in self.stages.iter()
+        {
+            if self.direction == MultistageDirection::Interpolation
+            {
+                scale /= stage.factor
+            }
+            delay = delay + c(stage.filter.b.len().saturating_sub(1))/two*c(scale);
+            if self.direction == MultistageDirection::Decimation
+            {
+                scale *= stage.factor
+            }
+        }
+        delay
+    }
+
+    /// Runs the signal through the cascade, each stage in a single polyphase pass.
+    ///
+    /// The output has the input's length divided, or multiplied, by the total factor, and is not compensated for the [delay](Multistage::delay).
+    pub fn apply(&self, x: &[T]) -> Vec<T>
+    {
+        let mut y = x.to_vec();
+        for stage in self.stages.iter()
+        {
+            let n = y.len();
+            y = match self.direction
+            {
+                MultistageDirection::Decimation => {
+                    let mut y = stage.filter.upfirdn(y, 1, stage.factor);
+                    y.truncate(n.div_ceil(stage.factor));
+                    y
+                },
+                MultistageDirection::Interpolation => {
+                    let mut y = stage.filter.upfirdn(y, stage.factor, 1);
+                    y.truncate(n*stage.factor);
+                    y
+                }
+            }
+        }
+        y
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::TAU;
+
+    use crate::operations::resampling::Multistage;
+
+    #[test]
+    fn test()
+    {
+        const R: usize = 64;
+
+        let decimator = Multistage::decimator(R, 0.8, 0.1, 80.0)
+            .unwrap();
+        assert!(decimator.stages.len() > 1);
+        assert_eq!(decimator.stages.iter().map(|stage| stage.factor).product::<usize>(), R);
+
+        // A tone in the passband comes out delayed, but otherwise intact.
+        let f = 0.3/(2.0*R as f64);
+        let x: Vec<f64> = (0..R*400).map(|n| (TAU*f*n as f64).sin())
+            .collect();
+        let y = decimator.apply(&x);
+        assert_eq!(y.len(), 400);
+        let d = decimator.delay();
+        for (m, y) in y.into_iter()
+            .enumerate()
+            .filter(|&(m, _)| (m*R) as f64 > 2.0*d)
+        {
+            assert!((y - (TAU*f*((m*R) as f64 - d)).sin()).abs() < 2e-2);
+        }
+
+        // A tone that would alias onto the passband tone is suppressed by the stopband attenuation, with a 6 dB margin.
+        let f_alias = (R as f64).recip() - f;
+        let x: Vec<f64> = (0..R*400).map(|n| (TAU*f_alias*n as f64).sin())
+            .collect();
+        let y = decimator.apply(&x);
+        for (_, y) in y.into_iter()
+            .enumerate()
+            .filter(|&(m, _)| (m*R) as f64 > 2.0*d)
+        {
+            assert!(y.abs() < 10f64.powf(-74.0/20.0));
+        }
+
+        let interpolator = Multistage::<f64>::interpolator(R, 0.8, 0.1, 80.0)
+            .unwrap();
+        assert_eq!(interpolator.stages.len(), decimator.stages.len());
+        assert_eq!(interpolator.apply(&[1.0; 10]).len(), 10*R);
+
+        // The same tone at the low rate comes out delayed, but otherwise intact, at the high rate.
+        let x: Vec<f64> = (0..400).map(|n| (TAU*f*(n*R) as f64).sin())
+            .collect();
+        let y = interpolator.apply(&x);
+        assert_eq!(y.len(), 400*R);
+        let d = interpolator.delay();
+        for (k, y) in y.into_iter()
+            .enumerate()
+            .filter(|&(k, _)| k as f64 > 2.0*d)
+        {
+            assert!((y - (TAU*f*(k as f64 - d)).sin()).abs() < 2e-2);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::VecDeque;
+
+use num::{rational::Ratio, Float, NumCast};
+use option_trait::Maybe;
+
+use crate::{gen::filter::{Fir1, Fir1Type, FilterGenError}, systems::Tf};
+
+/// Streaming polyphase resampler by a rational factor.
+///
+/// The anti-aliasing filter is designed the same way as by [resample](crate::operations::resampling::Resample::resample), but the
+/// signal may be fed in blocks of any length, and the output continues seamlessly across calls. Every output sample is computed
+/// as soon as the input sample it depends on has arrived, so the output is not compensated for the filter's [delay](Resampler::delay).
+#[derive(Debug, Clone)]
+pub struct Resampler<T>
+where
+    T: Float
+{
+    ratio: Ratio<usize>,
+    order: usize,
+    /// Polyphase components of the filter, scaled by the upsampling factor.
+    phases: Vec<Vec<T>>,
+    /// Past input samples, newest first.
+    history: VecDeque<T>,
+    /// Phase of the next output sample, relative to the newest input sample.
+    phase: usize
+}
+
+impl<T> Resampler<T>
+where
+    T: Float,
+    Tf<T, Vec<T>, ()>: Fir1<usize, [T; 1], T, (), false>
+{
+    pub fn new<N, W>(ratio: Ratio<usize>, order: N, cutoff: W) -> Result<Self, FilterGenError>
+    where
+        N: Maybe<usize>,
+        W: Maybe<T>
+    {
+        let ratio = Ratio::new(*ratio.numer(), (*ratio.denom()).max(1));
+        let p = (*ratio.numer()).max(1);
+        let q = *ratio.denom();
+        let pf = <T as NumCast>::from(p).unwrap();
+        let two = T::one() + T::one();
+
+        let order = order.into_option()
+            .unwrap_or(4);
+        let cutoff = cutoff.into_option()
+            .unwrap_or_else(|| pf/(two*<T as NumCast>::from(p.max(q)).unwrap()));
+
+        let h: Tf<T, Vec<T>, ()> = Tf::fir1(2*p*order, [cutoff/pf], Fir1Type::LowPass, (), false, ())?;
+        let phases = (0..p).map(|k| h.b.iter()
+                .skip(k)
+                .step_by(p)
+                .map(|&h| h*pf)
+                .collect()
+            ).collect();
+
+        let mut resampler = Self {
+            ratio,
+            order,
+            phases,
+            history: VecDeque::new(),
+            phase: 0
+        };
+        resampler.reset();
+        Ok(resampler)
+    }
+
+    pub fn ratio(&self) -> Ratio<usize>
+    {
+        self.ratio
+    }
+
+    /// Group delay of the resampling filter, in output samples.
+    pub fn delay(&self) -> T
+    {
+        <T as NumCast>::from(*self.ratio.numer()*self.order).unwrap()/<T as NumCast>::from(*self.ratio.denom()).unwrap()
+    }
+
+    /// Number of output samples the next `n` input samples will yield.
+    pub fn output_len(&self, n: usize) -> usize
+    {
+        let p = self.phases.len();
+        let q = *self.ratio.denom();
+        (n*p).saturating_sub(self.phase).div_ceil(q)
+    }
+
+    /// Resamples the next samples of the signal.
+    pub fn process(&mut self, x: &[T]) -> Vec<T>
+    {
+        let p = self.phases.len();
+        let q = *self.ratio.denom();
+        let mut y = Vec::with_capacity(self.output_len(x.len()));
+
+        for &x in x.iter()
+        {
+            self.history.pop_back();
+            self.history.push_front(x);
+
+            while self.phase < p
+            {
+                y.push(self.phases[self.phase].iter()
+                    .zip(self.history.iter())
+                    .map(|(&h, &x)| h*x)
+                    .fold(T::zero(), |a, b| a + b)
+                );
+                self.phase += q
+            }
+            self.phase -= p
+        }
+
+        y
+    }
+
+    /// Clears the signal history, as if the stream started anew.
+    pub fn reset(&mut self)
+    {
+        let taps = self.phases.iter()
+            .map(|h| h.len())
+            .max()
+            .unwrap_or(0);
+        self.history = core::iter::repeat(T::zero())
+            .take(taps.max(1))
+            .collect();
+        self.phase = 0
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::TAU;
+
+    use num::rational::Ratio;
+
+    use crate::operations::resampling::Resampler;
+
+    #[test]
+    fn test()
+    {
+        const FS: f64 = 44100.0;
+
+        let x: Vec<f64> = (0..4410).map(|i| (TAU*1000.0*i as f64/FS).sin())
+            .collect();
+
+        let mut whole = Resampler::new(Ratio::new(48000, 44100), (), ())
+            .unwrap();
+        let mut blocks = whole.clone();
+
+        let y = whole.process(&x);
+        let z: Vec<f64> = x.chunks(113)
+            .flat_map(|x| blocks.process(x))
+            .collect();
+
+        // 100 ms in, 100 ms out, regardless of the block sizes.
+        assert_eq!(y.len(), 4800);
+        assert_eq!(y, z);
+    }
+}
@@ -0,0 +1,103 @@
+use core::ops::AddAssign;
+
+use num::{complex::ComplexFloat, Zero};
+
+use crate::{quantities::{List, ListOrSingle, Lists}, util::ComplexOp, System, systems::Tf};
+
+pub trait UpFirDn<X, XX>: System
+where
+    Self::Set: ComplexOp<X>,
+    X: Into<<Self::Set as ComplexOp<X>>::Output> + ComplexFloat<Real = <Self::Set as ComplexFloat>::Real>,
+    XX: Lists<X>
+{
+    /// Upsamples by `up`, filters with the FIR filter, and downsamples by `down`, in one polyphase pass.
+    ///
+    /// Only the kept output samples are computed, and zeros inserted by upsampling are never multiplied. The full output of
+    /// `((N - 1)*up + len(h) - 1)/down + 1` samples is returned for a signal of length `N`.
+    fn upfirdn(&self, x: XX, up: usize, down: usize) -> XX::RowsMapped<Vec<<Self::Set as ComplexOp<X>>::Output>>;
+}
+
+impl<T, B, X, XX, Y> UpFirDn<X, XX> for Tf<T, B, ()>
+where
+    T: ComplexFloat + ComplexOp<X, Output = Y> + Into<Y>,
+    B: List<T>,
+    X: ComplexFloat<Real = T::Real> + Into<Y>,
+    XX: Lists<X, RowOwned: List<X>>,
+    Y: ComplexFloat<Real = T::Real> + AddAssign
+{
+    fn upfirdn(&self, x: XX, up: usize, down: usize) -> XX::RowsMapped<Vec<Y>>
+    {
+        let up = up.max(1);
+        let down = down.max(1);
+        let h: Vec<Y> = self.b.as_view_slice()
+            .iter()
+            .map(|&h| h.into())
+            .collect();
+
+        x.map_rows_into_owned(|x| {
+            let x: Vec<Y> = x.into_vec()
+                .into_iter()
+                .map(Into::into)
+                .collect();
+            if x.is_empty() || h.is_empty()
+            {
+                return vec![]
+            }
+            let len = ((x.len() - 1)*up + h.len() - 1)/down + 1;
+
+            (0..len).map(|m| {
+                    // Only taps aligned with a non-zero sample of the upsampled signal contribute.
+                    let n = m*down;
+                    let mut y = Y::zero();
+                    for (j, &h) in h.iter()
+                        .skip(n % up)
+                        .step_by(up)
+                        .enumerate()
+                    {
+                        if let Some(&x) = (n/up).checked_sub(j)
+                            .and_then(|i| x.get(i))
+                        {
+                            y += h*x
+                        }
+                    }
+                    y
+                }).collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use array_math::SliceMath;
+    use rand::distributions::uniform::SampleRange;
+
+    use crate::{operations::resampling::{Downsample, UpFirDn, Upsample}, systems::Tf};
+
+    #[test]
+    fn test()
+    {
+        let mut rng = rand::thread_rng();
+        let h: Vec<f64> = (0..25).map(|_| (-1.0..1.0).sample_single(&mut rng))
+            .collect();
+        let x: Vec<f64> = (0..50).map(|_| (-1.0..1.0).sample_single(&mut rng))
+            .collect();
+        let h = Tf::new(h, ());
+
+        const P: usize = 3;
+        const Q: usize = 2;
+        let y = h.upfirdn(x.clone(), P, Q);
+
+        // Equal to upsampling, convolving and downsampling separately.
+        let mut u: Vec<f64> = x.upsample(P, 0);
+        u.truncate((x.len() - 1)*P + 1);
+        let z: Vec<f64> = h.b.convolve_direct(&u);
+        let z: Vec<f64> = z.downsample(Q, 0);
+        assert_eq!(y.len(), z.len());
+        for (y, z) in y.into_iter()
+            .zip(z)
+        {
+            assert!((y - z).abs() < 1e-12)
+        }
+    }
+}
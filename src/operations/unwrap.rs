@@ -0,0 +1,149 @@
+use ndarray::{Array, Axis, Dimension};
+use num::{traits::FloatConst, Float};
+use option_trait::Maybe;
+
+use crate::quantities::{ListOrSingle, Lists, OwnedList};
+
+/// Unwraps the phase of consecutive samples in place.
+///
+/// Wherever the phase jumps by at least `tolerance`, multiples of 2π are added to all following samples to bring the jump within
+/// ±π. NaN samples are left as they are, and do not break the unwrapping of the samples after them.
+fn unwrap_lane<'a, T>(x: impl IntoIterator<Item = &'a mut T>, tolerance: T)
+where
+    T: Float + FloatConst + 'a
+{
+    let mut prev = None;
+    let mut correction = T::zero();
+    for x in x
+    {
+        if x.is_nan()
+        {
+            continue
+        }
+        let raw = *x;
+        if let Some(prev) = prev
+        {
+            let d = raw - prev;
+            if d.abs() >= tolerance
+            {
+                // Jumps of exactly ±π are kept in their own direction.
+                let mut ds = (d + T::PI()) % T::TAU();
+                if ds < T::zero()
+                {
+                    ds = ds + T::TAU()
+                }
+                ds = ds - T::PI();
+                if ds == -T::PI() && d > T::zero()
+                {
+                    ds = T::PI()
+                }
+                correction = correction + ds - d
+            }
+        }
+        prev = Some(raw);
+        *x = raw + correction
+    }
+}
+
+pub trait Unwrap<T>: Lists<T>
+where
+    T: Float
+{
+    /// Unwraps the phase along each row.
+    ///
+    /// Jumps of at least `tolerance` (default π) between consecutive samples are taken to be phase wraps, and are corrected by
+    /// multiples of 2π.
+    fn unwrap<TOL>(self, tolerance: TOL) -> Self::RowsMapped<Self::RowOwned>
+    where
+        TOL: Maybe<T>;
+}
+
+impl<T, L> Unwrap<T> for L
+where
+    T: Float + FloatConst,
+    L: Lists<T, RowOwned: OwnedList<T>>
+{
+    fn unwrap<TOL>(self, tolerance: TOL) -> Self::RowsMapped<Self::RowOwned>
+    where
+        TOL: Maybe<T>
+    {
+        let tolerance = tolerance.into_option()
+            .unwrap_or_else(T::PI);
+
+        self.map_rows_into_owned(|mut x| {
+            unwrap_lane(x.as_mut_slice(), tolerance);
+            x
+        })
+    }
+}
+
+pub trait UnwrapAxis<T>: Sized
+where
+    T: Float
+{
+    /// Unwraps the phase along the given axis of a multi-dimensional array.
+    ///
+    /// Jumps of at least `tolerance` (default π) between consecutive samples are taken to be phase wraps, and are corrected by
+    /// multiples of 2π.
+    fn unwrap_axis<TOL>(self, tolerance: TOL, axis: Axis) -> Self
+    where
+        TOL: Maybe<T>;
+}
+
+impl<T, D> UnwrapAxis<T> for Array<T, D>
+where
+    T: Float + FloatConst,
+    D: Dimension
+{
+    fn unwrap_axis<TOL>(mut self, tolerance: TOL, axis: Axis) -> Self
+    where
+        TOL: Maybe<T>
+    {
+        let tolerance = tolerance.into_option()
+            .unwrap_or_else(T::PI);
+
+        for lane in self.lanes_mut(axis)
+        {
+            unwrap_lane(lane, tolerance)
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod test
+{
+    use core::f64::consts::{PI, TAU};
+
+    use ndarray::{Array2, Axis};
+
+    use crate::operations::{Unwrap, UnwrapAxis};
+
+    #[test]
+    fn test()
+    {
+        const N: usize = 100;
+
+        let phase: Vec<f64> = (0..N).map(|i| 0.3*i as f64 - 0.002*(i*i) as f64)
+            .collect();
+        let wrapped: Vec<f64> = phase.iter()
+            .map(|&p| (p + PI).rem_euclid(TAU) - PI)
+            .collect();
+
+        let unwrapped = wrapped.clone()
+            .unwrap(());
+        for (u, p) in unwrapped.into_iter()
+            .zip(phase.iter())
+        {
+            assert!((u - p).abs() < 1e-9)
+        }
+
+        // The same phase down the columns of an array.
+        let x = Array2::from_shape_fn((N, 3), |(i, _)| wrapped[i]);
+        let y = x.unwrap_axis((), Axis(0));
+        for ((i, _), &y) in y.indexed_iter()
+        {
+            assert!((y - phase[i]).abs() < 1e-9)
+        }
+    }
+}
@@ -1,6 +1,7 @@
 moddef::moddef!(
     flat(pub) mod {
         qmf,
+        quantize_coefficients,
         sftrans,
         stabilize,
         zftrans
@@ -0,0 +1,79 @@
+use num::{Complex, Float};
+
+use crate::{operations::{EncodeOverflow, FixedPointFormat, QuantizeRounding}, System, systems::{Sos, Tf, Zpk}};
+
+/// Quantizes the coefficients of a filter to a fixed-point format.
+///
+/// The leading denominator coefficient is left as it is, since it is assumed normalized to one and implicit in the implementation.
+/// For zero-pole-gain form, the real and imaginary parts of the zeros and poles are quantized, along with the gain.
+pub trait QuantizeCoefficients: System + Sized
+{
+    fn quantize_coefficients(self, format: FixedPointFormat, rounding: QuantizeRounding, overflow: EncodeOverflow) -> Self;
+}
+
+impl<T> QuantizeCoefficients for Tf<T, Vec<T>, Vec<T>>
+where
+    T: Float,
+    Self: System<Set = T>
+{
+    fn quantize_coefficients(self, format: FixedPointFormat, rounding: QuantizeRounding, overflow: EncodeOverflow) -> Self
+    {
+        let q = |x: T| format.quantize(x, rounding, overflow);
+
+        Tf::new(
+            self.b.into_inner()
+                .into_iter()
+                .map(q)
+                .collect(),
+            self.a.into_inner()
+                .into_iter()
+                .enumerate()
+                .map(|(i, a)| if i == 0 {a} else {q(a)})
+                .collect()
+        )
+    }
+}
+
+impl<T> QuantizeCoefficients for Sos<T, [T; 3], [T; 3], Vec<Tf<T, [T; 3], [T; 3]>>>
+where
+    T: Float,
+    Self: System<Set = T>
+{
+    fn quantize_coefficients(self, format: FixedPointFormat, rounding: QuantizeRounding, overflow: EncodeOverflow) -> Self
+    {
+        let q = |x: T| format.quantize(x, rounding, overflow);
+
+        Sos::new(self.sos.into_inner()
+            .into_iter()
+            .map(|tf| {
+                let b = tf.b.into_inner();
+                let a = tf.a.into_inner();
+                Tf::new(b.map(q), [a[0], q(a[1]), q(a[2])])
+            }).collect()
+        )
+    }
+}
+
+impl<T> QuantizeCoefficients for Zpk<Complex<T>, Vec<Complex<T>>, Vec<Complex<T>>, T>
+where
+    T: Float,
+    Self: System<Set = T>
+{
+    fn quantize_coefficients(self, format: FixedPointFormat, rounding: QuantizeRounding, overflow: EncodeOverflow) -> Self
+    {
+        let q = |x: T| format.quantize(x, rounding, overflow);
+        let qc = |z: Complex<T>| Complex::new(q(z.re), q(z.im));
+
+        Zpk::new(
+            self.z.into_inner()
+                .into_iter()
+                .map(qc)
+                .collect(),
+            self.p.into_inner()
+                .into_iter()
+                .map(qc)
+                .collect(),
+            q(self.k)
+        )
+    }
+}
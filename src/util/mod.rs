@@ -13,6 +13,7 @@ moddef::moddef!(
         not_range,
         overlay,
         result_or_ok,
+        sorted_window,
         truncate_im,
         two_sided_range
     },
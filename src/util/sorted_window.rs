@@ -0,0 +1,105 @@
+use core::cmp::Ordering;
+
+use num::Float;
+
+/// Multiset of the samples in a sliding window, kept in sorted order.
+///
+/// Insertion and removal find their position by binary search, so any order statistic of the window, such as the median, is
+/// available at any time without sorting the window anew. NaN is ordered after all other values.
+#[derive(Debug, Clone, Default)]
+pub struct SortedWindow<T>
+where
+    T: Float
+{
+    values: Vec<T>
+}
+
+impl<T> SortedWindow<T>
+where
+    T: Float
+{
+    pub fn new() -> Self
+    {
+        Self {
+            values: vec![]
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self
+    {
+        Self {
+            values: Vec::with_capacity(capacity)
+        }
+    }
+
+    fn cmp(a: &T, b: &T) -> Ordering
+    {
+        a.partial_cmp(b)
+            .unwrap_or_else(|| a.is_nan().cmp(&b.is_nan()))
+    }
+
+    pub fn insert(&mut self, x: T)
+    {
+        let i = self.values.partition_point(|y| Self::cmp(y, &x) == Ordering::Less);
+        self.values.insert(i, x)
+    }
+
+    /// Removes one sample equal to `x`, and returns whether there was one.
+    pub fn remove(&mut self, x: T) -> bool
+    {
+        match self.values.binary_search_by(|y| Self::cmp(y, &x))
+        {
+            Ok(i) => {
+                self.values.remove(i);
+                true
+            },
+            Err(_) => false
+        }
+    }
+
+    pub fn clear(&mut self)
+    {
+        self.values.clear()
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.values.is_empty()
+    }
+
+    /// The samples of the window, in ascending order.
+    pub fn as_slice(&self) -> &[T]
+    {
+        &self.values
+    }
+
+    /// The `rank`-th smallest sample of the window, starting at zero.
+    pub fn get(&self, rank: usize) -> Option<T>
+    {
+        self.values.get(rank)
+            .copied()
+    }
+
+    /// The median of the window. For an even number of samples, this is the mean of the two middle samples.
+    pub fn median(&self) -> Option<T>
+    {
+        let n = self.values.len();
+        if n == 0
+        {
+            return None
+        }
+        if n % 2 == 1
+        {
+            Some(self.values[n/2])
+        }
+        else
+        {
+            Some((self.values[n/2 - 1] + self.values[n/2])/(T::one() + T::one()))
+        }
+    }
+}